@@ -0,0 +1,675 @@
+//! Statistical confidence of deviation estimates:
+//! equivalent degrees of freedom (EDF), chi-squared
+//! and bootstrap confidence intervals
+//!
+//! url: <https://tf.nist.gov/general/pdf/1990.pdf>
+//! url: <https://www.nist.gov/publications/handbook-frequency-stability-analysis>
+
+use crate::{tau, utils, Deviation, Error};
+use rand::Rng;
+
+/// Maximal number of terms in Greenhall's basic sum,   
+/// approximations are used above
+const J_MAX: f64 = 100.0_f64;
+
+/// Returns equivalent degrees of freedom of desired deviation.   
+/// Greenhall & Riley generalized algorithm is used for   
+/// Allan, modified Allan, Hadamard and modified Hadamard families,   
+/// NIST SP1065 empirical formulas for total and theo statistics.   
+/// Parabolic deviation is approximated by its modified Allan counterpart.   
+/// calc: deviation   
+/// alpha: fractional frequency power law exponent (S_y(f) ~ f^alpha),   
+/// clamped to the range supported by the statistic   
+/// tau_u: tau / tau0   
+/// size: number of phase samples   
+/// overlapping: true for overlapped deviation
+pub fn edf (calc: Deviation, alpha: i32, tau_u: f64, size: usize, overlapping: bool) -> f64 {
+    let m = tau_u.max(1.0_f64).round();
+    let n = size as f64;
+    match calc {
+        Deviation::Allan | Deviation::GapResistant => greenhall_edf(alpha, 2, m, n, overlapping, false),
+        Deviation::Modified | Deviation::Time | Deviation::Parabolic => greenhall_edf(alpha, 2, m, n, true, true),
+        Deviation::Hadamard => greenhall_edf(alpha, 3, m, n, overlapping, false),
+        Deviation::ModifiedHadamard => greenhall_edf(alpha, 3, m, n, true, true),
+        Deviation::Total => totvar_edf(alpha, m, n),
+        Deviation::ModifiedTotal | Deviation::TimeTotal => mtotvar_edf(alpha, m, n),
+        Deviation::HadamardTotal => htotvar_edf(alpha, m, n),
+        Deviation::Theo1 | Deviation::TheoBR => theo1_edf(alpha, tau_u, n),
+        Deviation::TheoH => {
+            // same rule as theoH evaluation
+            if tau::is_theo_multiple(tau_u) && tau_u > 0.1_f64 * (n - 1.0_f64) {
+                theo1_edf(alpha, tau_u, n)
+            } else {
+                greenhall_edf(alpha, 2, m, n, true, false)
+            }
+        },
+    }
+}
+
+/// Greenhall & Riley generalized EDF algorithm.   
+/// d: differencing order (2: Allan, 3: Hadamard)   
+/// m: averaging factor   
+/// n: number of phase samples   
+/// modified: true for modified variances
+fn greenhall_edf (alpha: i32, d: usize, m: f64, n: f64, overlapping: bool, modified: bool) -> f64 {
+    let alpha = alpha.clamp(2 - 2 * d as i32, 2);
+    let df = d as f64;
+    // filter factor
+    let f = match modified {
+        true => 1.0_f64,
+        false => m,
+    };
+    // stride factor
+    let s = match overlapping {
+        true => m,
+        false => 1.0_f64,
+    };
+    let l = m / f + m * df;
+    let big_m = (1.0_f64 + (s * (n - l) / m).floor()).max(1.0_f64);
+    let j = big_m.min((df + 1.0_f64) * s);
+    let r = big_m / s;
+
+    let inv_edf = if modified {
+        if j <= J_MAX {
+            basic_sum(j, big_m, s, Some(1.0_f64), alpha, d)
+                / greenhall_sz(0.0_f64, Some(1.0_f64), alpha, d).powf(2.0_f64) / big_m
+        } else if r > df + 1.0_f64 {
+            let (a0, a1) = greenhall_table1(alpha, d);
+            (a0 - a1 / r) / r
+        } else {
+            let m_prime = J_MAX / r;
+            basic_sum(J_MAX, J_MAX, m_prime, Some(1.0_f64), alpha, d)
+                / greenhall_sz(0.0_f64, Some(1.0_f64), alpha, d).powf(2.0_f64) / J_MAX
+        }
+    } else if alpha <= 0 {
+        if j <= J_MAX {
+            let f_prime = match m * (df + 1.0_f64) <= J_MAX {
+                true => Some(m),
+                false => None,
+            };
+            basic_sum(j, big_m, s, f_prime, alpha, d)
+                / greenhall_sz(0.0_f64, f_prime, alpha, d).powf(2.0_f64) / big_m
+        } else if r > df + 1.0_f64 {
+            let (a0, a1) = greenhall_table2(alpha, d);
+            (a0 - a1 / r) / r
+        } else {
+            let m_prime = J_MAX / r;
+            basic_sum(J_MAX, J_MAX, m_prime, None, alpha, d)
+                / greenhall_sz(0.0_f64, None, alpha, d).powf(2.0_f64) / J_MAX
+        }
+    } else if alpha == 1 {
+        if j <= J_MAX {
+            basic_sum(j, big_m, s, Some(m), alpha, d)
+                / greenhall_sz(0.0_f64, Some(m), alpha, d).powf(2.0_f64) / big_m
+        } else if r > df + 1.0_f64 {
+            let (a0, a1) = greenhall_table2(alpha, d);
+            let (b0, b1) = greenhall_table3(d);
+            (a0 - a1 / r) / r / (b0 + b1 * m.ln()).powf(2.0_f64)
+        } else {
+            let m_prime = J_MAX / r;
+            let (b0, b1) = greenhall_table3(d);
+            basic_sum(J_MAX, J_MAX, m_prime, Some(m_prime), alpha, d)
+                / (b0 + b1 * m.ln()).powf(2.0_f64) / J_MAX
+        }
+    } else {
+        let k_max = r.ceil();
+        if k_max <= df {
+            // terms spaced k * m apart are correlated by
+            // rho(k) = C(2d, d+k) / C(2d, d), only K - 1 lags remain
+            let rho = |k: usize| binomial(2 * d, d + k) / binomial(2 * d, d);
+            let mut sum = 1.0_f64;
+            for k in 1..k_max as usize {
+                sum += 2.0_f64 * (1.0_f64 - k as f64 / r) * rho(k).powf(2.0_f64)
+            }
+            sum / big_m
+        } else {
+            let a0 = binomial(4 * d, 2 * d) / binomial(2 * d, d).powf(2.0_f64);
+            let a1 = df / 2.0_f64;
+            (a0 - a1 / r) / big_m
+        }
+    };
+    1.0_f64 / inv_edf
+}
+
+/// Greenhall's basic sum.   
+/// f: filter factor, None stands for infinite filter factor
+fn basic_sum (j: f64, m: f64, s: f64, f: Option<f64>, alpha: i32, d: usize) -> f64 {
+    let mut sum = greenhall_sz(0.0_f64, f, alpha, d).powf(2.0_f64);
+    sum += (1.0_f64 - j / m) * greenhall_sz(j / s, f, alpha, d).powf(2.0_f64);
+    for k in 1..j as usize {
+        let k = k as f64;
+        sum += 2.0_f64 * (1.0_f64 - k / m) * greenhall_sz(k / s, f, alpha, d).powf(2.0_f64)
+    }
+    sum
+}
+
+fn greenhall_sz (t: f64, f: Option<f64>, alpha: i32, d: usize) -> f64 {
+    let sx = |t: f64| greenhall_sx(t, f, alpha);
+    match d {
+        1 => 2.0_f64 * sx(t) - sx(t - 1.0_f64) - sx(t + 1.0_f64),
+        2 => 6.0_f64 * sx(t) - 4.0_f64 * (sx(t - 1.0_f64) + sx(t + 1.0_f64))
+                + sx(t - 2.0_f64) + sx(t + 2.0_f64),
+        _ => 20.0_f64 * sx(t) - 15.0_f64 * (sx(t - 1.0_f64) + sx(t + 1.0_f64))
+                + 6.0_f64 * (sx(t - 2.0_f64) + sx(t + 2.0_f64))
+                - sx(t - 3.0_f64) - sx(t + 3.0_f64),
+    }
+}
+
+fn greenhall_sx (t: f64, f: Option<f64>, alpha: i32) -> f64 {
+    match f {
+        None => greenhall_sw(t, alpha + 2),
+        Some(f) => {
+            f.powf(2.0_f64) * (2.0_f64 * greenhall_sw(t, alpha)
+                - greenhall_sw(t - 1.0_f64 / f, alpha) - greenhall_sw(t + 1.0_f64 / f, alpha))
+        },
+    }
+}
+
+fn greenhall_sw (t: f64, alpha: i32) -> f64 {
+    let log = |p: i32| -> f64 {
+        match t == 0.0_f64 {
+            true => 0.0_f64,
+            false => t.powi(p) * t.abs().ln(),
+        }
+    };
+    match alpha {
+        a if a >= 2 => -t.abs(),
+        1 => log(2),
+        0 => t.abs().powi(3),
+        -1 => log(4),
+        -2 => t.abs().powi(5),
+        -3 => log(6),
+        _ => t.abs().powi(7),
+    }
+}
+
+/// Modified variances (a0, a1) coefficients
+fn greenhall_table1 (alpha: i32, d: usize) -> (f64, f64) {
+    const TABLE: [[(f64, f64); 3]; 7] = [
+        [(2.0/3.0, 1.0/3.0), (7.0/9.0, 1.0/2.0), (22.0/25.0, 2.0/3.0)],
+        [(0.840, 0.345), (0.997, 0.616), (1.141, 0.843)],
+        [(1.079, 0.368), (1.033, 0.607), (1.184, 0.848)],
+        [(f64::NAN, f64::NAN), (1.048, 0.534), (1.180, 0.816)],
+        [(f64::NAN, f64::NAN), (1.302, 0.535), (1.175, 0.777)],
+        [(f64::NAN, f64::NAN), (f64::NAN, f64::NAN), (1.194, 0.703)],
+        [(f64::NAN, f64::NAN), (f64::NAN, f64::NAN), (1.489, 0.702)],
+    ];
+    TABLE[(2 - alpha) as usize][d - 1]
+}
+
+/// Unmodified variances (a0, a1) coefficients
+fn greenhall_table2 (alpha: i32, d: usize) -> (f64, f64) {
+    const TABLE: [[(f64, f64); 3]; 7] = [
+        [(3.0/2.0, 1.0/2.0), (35.0/18.0, 1.0), (231.0/100.0, 3.0/2.0)],
+        [(78.6, 25.2), (790.0, 410.0), (9950.0, 6520.0)],
+        [(2.0/3.0, 1.0/6.0), (2.0/3.0, 1.0/3.0), (7.0/9.0, 1.0/2.0)],
+        [(f64::NAN, f64::NAN), (0.852, 0.375), (0.997, 0.617)],
+        [(f64::NAN, f64::NAN), (1.079, 0.368), (1.033, 0.607)],
+        [(f64::NAN, f64::NAN), (f64::NAN, f64::NAN), (1.053, 0.553)],
+        [(f64::NAN, f64::NAN), (f64::NAN, f64::NAN), (1.302, 0.535)],
+    ];
+    TABLE[(2 - alpha) as usize][d - 1]
+}
+
+/// Unmodified variances, flicker PM (b0, b1) coefficients
+fn greenhall_table3 (d: usize) -> (f64, f64) {
+    const TABLE: [(f64, f64); 3] = [(6.0, 4.0), (15.23, 12.0), (47.8, 40.0)];
+    TABLE[d - 1]
+}
+
+fn binomial (n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0_f64, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Total variance EDF, b * T/tau - c (NIST SP1065 §5.2.11).   
+/// Phase noises follow overlapping Allan variance
+fn totvar_edf (alpha: i32, m: f64, n: f64) -> f64 {
+    let (b, c) = match alpha {
+        a if a > 0 => return greenhall_edf(alpha, 2, m, n, true, false),
+        0 => (1.50_f64, 0.0_f64),
+        -1 => (1.17_f64, 0.22_f64),
+        _ => (0.93_f64, 0.36_f64),
+    };
+    b * n / m - c
+}
+
+/// Modified total variance EDF, b * T/tau - c (NIST SP1065 §5.2.12)
+fn mtotvar_edf (alpha: i32, m: f64, n: f64) -> f64 {
+    let (b, c) = match alpha {
+        a if a > 1 => (1.90_f64, 2.10_f64),
+        1 => (1.20_f64, 1.40_f64),
+        0 => (1.10_f64, 1.20_f64),
+        -1 => (0.85_f64, 0.50_f64),
+        _ => (0.75_f64, 0.31_f64),
+    };
+    b * n / m - c
+}
+
+/// Hadamard total variance EDF, (T/tau) / (b0 + b1 * tau/T)   
+/// (NIST SP1065 §5.2.13). Phase noises follow white FM
+fn htotvar_edf (alpha: i32, m: f64, n: f64) -> f64 {
+    let (b0, b1) = match alpha {
+        a if a >= 0 => (0.559_f64, 1.004_f64),
+        -1 => (0.868_f64, 1.140_f64),
+        _ => (0.938_f64, 1.696_f64),
+    };
+    (n / m) / (b0 + b1 * m / n)
+}
+
+/// Theo1 EDF (NIST SP1065 §5.2.14).   
+/// The random walk FM formula turns negative at long tau,   
+/// the number of independent Theo1 terms (N - m) / m is used there.   
+/// r: tau / tau0
+fn theo1_edf (alpha: i32, r: f64, n: f64) -> f64 {
+    let value = match alpha {
+        a if a > 1 => {
+            0.86_f64 * (n + 1.0_f64) * (n - 4.0_f64 * r / 3.0_f64) / (n - r)
+                * r / (r + 1.14_f64)
+        },
+        1 => {
+            (4.798_f64 * n * n - 6.374_f64 * n * r + 12.387_f64 * r)
+                / ((r + 36.6_f64).powf(0.5_f64) * (n - r) * (n + 1.0_f64))
+                * r / (r + 0.3_f64)
+        },
+        0 => {
+            ((4.1_f64 * n + 0.8_f64) / r - (3.1_f64 * n + 6.5_f64) / n)
+                * r.powf(1.5_f64) / (r.powf(1.5_f64) + 5.2_f64)
+        },
+        -1 => {
+            (2.0_f64 * n * n - 1.3_f64 * n * r - 3.5_f64 * r) / (n * r)
+                * r.powf(3.0_f64) / (r.powf(3.0_f64) + 2.3_f64)
+        },
+        _ => {
+            (4.4_f64 * n - 2.0_f64) / (2.9_f64 * r)
+                * ((4.4_f64 * n - 1.0_f64).powf(2.0_f64) - 8.6_f64 * r * (4.4_f64 * n - 1.0_f64)
+                    + 11.4_f64 * r * r)
+                / (4.4_f64 * n - 3.0_f64).powf(2.0_f64)
+        },
+    };
+    match value > 0.0_f64 && value.is_finite() {
+        true => value,
+        false => {
+            let m = r / 0.75_f64;
+            (n - m).max(1.0_f64) / m
+        },
+    }
+}
+
+/// Returns (lower, upper) bounds of the confidence interval   
+/// of a deviation estimate, from the chi-squared distribution.   
+/// dev: deviation estimate   
+/// edf: equivalent degrees of freedom of the estimate   
+/// confidence: confidence level, in ]0, 1[   
+/// edf must be finite and positive
+pub fn confidence_interval (dev: f64, edf: f64, confidence: f64) -> Result<(f64, f64), Error> {
+    if !(confidence > 0.0_f64 && confidence < 1.0_f64) {
+        return Err(Error::InvalidConfidenceLevel)
+    }
+    let p = (1.0_f64 - confidence) / 2.0_f64;
+    let lower = dev * (edf / chi2_quantile(1.0_f64 - p, edf)?).powf(0.5_f64);
+    let upper = dev * (edf / chi2_quantile(p, edf)?).powf(0.5_f64);
+    Ok((lower, upper))
+}
+
+/// Returns the chi-squared distribution quantile,   
+/// such as P(X < x) = p, for `dof` degrees of freedom   
+/// (not necessarily integer, but finite and positive).   
+/// p: probability, in ]0, 1[
+pub fn chi2_quantile (p: f64, dof: f64) -> Result<f64, Error> {
+    if !(dof.is_finite() && dof > 0.0_f64) {
+        return Err(Error::InvalidDegreesOfFreedom)
+    }
+    if !(p > 0.0_f64 && p < 1.0_f64) {
+        return Err(Error::InvalidConfidenceLevel)
+    }
+    let cdf = |x: f64| gamma_p(dof / 2.0_f64, x / 2.0_f64);
+    // bracket the quantile
+    let mut hi = dof.max(1.0_f64);
+    while cdf(hi) < p {
+        hi *= 2.0_f64
+    }
+    let mut lo = 0.0_f64;
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.0_f64;
+        if cdf(mid) < p {
+            lo = mid
+        } else {
+            hi = mid
+        }
+        if hi - lo < 1.0E-12 * hi {
+            break
+        }
+    }
+    Ok((lo + hi) / 2.0_f64)
+}
+
+/// Regularized lower incomplete gamma function P(a, x),   
+/// series expansion below a + 1, continued fraction above
+fn gamma_p (a: f64, x: f64) -> f64 {
+    if x <= 0.0_f64 {
+        return 0.0_f64
+    }
+    let ln_prefactor = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0_f64 {
+        let mut term = 1.0_f64 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..1000 {
+            ap += 1.0_f64;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1.0E-15 {
+                break
+            }
+        }
+        sum * ln_prefactor.exp()
+    } else {
+        // modified Lentz evaluation of the continued fraction
+        let tiny = 1.0E-300_f64;
+        let mut b = x + 1.0_f64 - a;
+        let mut c = 1.0_f64 / tiny;
+        let mut d = 1.0_f64 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0_f64;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny
+            }
+            d = 1.0_f64 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0_f64).abs() < 1.0E-15 {
+                break
+            }
+        }
+        1.0_f64 - ln_prefactor.exp() * h
+    }
+}
+
+/// Lanczos approximation of ln(Gamma(x)), x > 0
+fn ln_gamma (x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5_f64 {
+        // reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0_f64 - x)
+    }
+    let x = x - 1.0_f64;
+    let mut sum = COEFFS[0];
+    for (i, c) in COEFFS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64)
+    }
+    let t = x + 7.5_f64;
+    0.5_f64 * (2.0_f64 * std::f64::consts::PI).ln() + (x + 0.5_f64) * t.ln() - t + sum.ln()
+}
+
+/// Block bootstrap options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bootstrap {
+    /// number of resampled series
+    pub iterations: usize,
+    /// length of resampled blocks (samples),   
+    /// N^(1/3) when not specified
+    pub block_len: Option<usize>,
+    /// confidence level, in ]0, 1[
+    pub confidence: f64,
+}
+
+impl Default for Bootstrap {
+    /// Builds default `Bootstrap` options,   
+    /// 1000 iterations at 95% confidence
+    fn default() -> Bootstrap {
+        Bootstrap {
+            iterations: 1000,
+            block_len: None,
+            confidence: 0.95_f64,
+        }
+    }
+}
+
+/// Moving block bootstrap: evaluates `estimator` over resampled   
+/// series and returns the (lower, upper) percentile confidence   
+/// interval of each estimated value.   
+/// Fractional data are resampled directly, phase data   
+/// are resampled by increments (fractional frequency) then integrated back.   
+/// Synchronized series share the same resampling, which preserves   
+/// their correlation, for example for three cornered hat estimates.   
+/// series: input series, with identical length   
+/// is_fractional: true if input series are made of fractional (n.a) data   
+/// opts: bootstrap options   
+/// rng: random generator, seed it for reproducible intervals   
+/// estimator: evaluates the statistics of interest   
+/// over a set of (resampled) series
+pub fn bootstrap<R, F> (series: &[&[f64]], is_fractional: bool, opts: &Bootstrap, rng: &mut R, estimator: F)
+        -> Result<Vec<(f64,f64)>, Error>
+where
+    R: Rng,
+    F: Fn(&[Vec<f64>]) -> Result<Vec<f64>, Error>,
+{
+    if !(opts.confidence > 0.0_f64 && opts.confidence < 1.0_f64) {
+        return Err(Error::InvalidConfidenceLevel)
+    }
+    if opts.iterations == 0 || opts.block_len == Some(0) {
+        return Err(Error::InvalidBootstrapError)
+    }
+    let size = series.first()
+        .map(|s| s.len())
+        .unwrap_or(0);
+    if series.iter().any(|s| s.len() != size) {
+        return Err(Error::SeriesLengthMismatch)
+    }
+    if size < 3 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let increments: Vec<Vec<f64>> = series.iter()
+        .map(|s| match is_fractional {
+            true => s.to_vec(),
+            false => utils::diff(&s.to_vec(), None),
+        })
+        .collect();
+    let len = increments[0].len();
+    let block_len = opts.block_len
+        .unwrap_or((len as f64).cbrt().ceil() as usize)
+        .min(len);
+
+    let mut estimates: Vec<Vec<f64>> = Vec::new();
+    for _ in 0..opts.iterations {
+        // shared block starting points
+        let mut indexes: Vec<usize> = Vec::with_capacity(len);
+        while indexes.len() < len {
+            let start = rng.gen_range(0..=len - block_len);
+            indexes.extend((start..start + block_len).take(len - indexes.len()))
+        }
+        let resampled: Vec<Vec<f64>> = series.iter()
+            .zip(increments.iter())
+            .map(|(s, incr)| {
+                let incr: Vec<f64> = indexes.iter()
+                    .map(|i| incr[*i])
+                    .collect();
+                match is_fractional {
+                    true => incr,
+                    false => {
+                        let mut phase: Vec<f64> = Vec::with_capacity(size);
+                        phase.push(s[0]);
+                        for (i, dx) in incr.iter().enumerate() {
+                            phase.push(phase[i] + dx)
+                        }
+                        phase
+                    },
+                }
+            })
+            .collect();
+        for (i, value) in estimator(&resampled)?.iter().enumerate() {
+            if i == estimates.len() {
+                estimates.push(Vec::with_capacity(opts.iterations))
+            }
+            if value.is_finite() {
+                estimates[i].push(*value)
+            }
+        }
+    }
+    let p = (1.0_f64 - opts.confidence) / 2.0_f64;
+    Ok(estimates.iter_mut()
+        .map(|values| {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            (percentile(values, p), percentile(values, 1.0_f64 - p))
+        })
+        .collect())
+}
+
+/// Returns desired percentile of sorted values,   
+/// linearly interpolated
+fn percentile (sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN
+    }
+    let pos = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_greenhall_edf() {
+        let (n, m) = (1000.0_f64, 10.0_f64);
+        // white PM, m = 1: second differences are a MA(2) process,
+        // edf = M / (1 + 2 * (rho1^2 + rho2^2))
+        let expected = (n - 2.0) * 36.0 / 70.0;
+        let value = edf(Deviation::Allan, 2, 1.0, n as usize, false);
+        assert!((value / expected - 1.0).abs() < 1.0E-3);
+        // against Howe's closed forms, overlapping adev
+        let expected = (n + 1.0) * (n - 2.0 * m) / 2.0 / (n - m);
+        let value = edf(Deviation::Allan, 2, m, n as usize, true);
+        assert!((value / expected - 1.0).abs() < 0.05);
+        let expected = (3.0 * (n - 1.0) / 2.0 / m - 2.0 * (n - 2.0) / n)
+            * 4.0 * m * m / (4.0 * m * m + 5.0);
+        let value = edf(Deviation::Allan, 0, m, n as usize, true);
+        assert!((value / expected - 1.0).abs() < 0.1);
+        // white PM at long tau, few terms remain (K <= d)
+        for calc in [Deviation::Allan, Deviation::Hadamard] {
+            for m in [250.0, 333.0, 400.0, 499.0] {
+                let value = edf(calc, 2, m, n as usize, true);
+                assert!(value.is_finite() && value > 0.0)
+            }
+        }
+        // 200 terms, none share a sample
+        let value = edf(Deviation::Allan, 2, 400.0, n as usize, true);
+        assert!((value - 200.0).abs() < 1.0E-9);
+        // overlapping increases confidence
+        for alpha in [2, 1, 0, -1, -2] {
+            let edf_o = edf(Deviation::Allan, alpha, m, n as usize, true);
+            let edf_no = edf(Deviation::Allan, alpha, m, n as usize, false);
+            assert!(edf_o > edf_no);
+            assert!(edf_no > 0.0)
+        }
+        // confidence decreases with tau
+        for calc in [Deviation::Modified, Deviation::Hadamard, Deviation::ModifiedHadamard] {
+            for alpha in [2, 1, 0, -1, -2] {
+                let mut prev = f64::MAX;
+                for m in [1.0, 2.0, 4.0, 16.0, 64.0, 256.0] {
+                    let value = edf(calc, alpha, m, n as usize, true);
+                    assert!(value > 0.0 && value < prev);
+                    prev = value
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_theo1_edf() {
+        let n = 1000;
+        // whole feasible Theo1 range, tau = 0.75 * m * tau0
+        for calc in [Deviation::Theo1, Deviation::TheoBR, Deviation::TheoH] {
+            for alpha in [2, 1, 0, -1, -2] {
+                for m in (2..n).step_by(2) {
+                    let value = edf(calc, alpha, 0.75 * m as f64, n, true);
+                    assert!(value.is_finite() && value > 0.0)
+                }
+            }
+        }
+        // SP1065 formula where it holds
+        let value = edf(Deviation::Theo1, -2, 75.0, n, true);
+        let (n, r) = (n as f64, 75.0);
+        let expected = (4.4 * n - 2.0) / (2.9 * r)
+            * ((4.4 * n - 1.0).powi(2) - 8.6 * r * (4.4 * n - 1.0) + 11.4 * r * r)
+            / (4.4 * n - 3.0).powi(2);
+        assert_eq!(value, expected);
+    }
+    #[test]
+    fn test_chi2_quantile() {
+        // reference values
+        let expected = [
+            (0.5, 1.0, 0.454_936),
+            (0.95, 1.0, 3.841_459),
+            (0.025, 10.0, 3.246_973),
+            (0.975, 10.0, 20.483_177),
+            (0.95, 100.0, 124.342_1),
+        ];
+        for (p, dof, x) in expected {
+            assert!((chi2_quantile(p, dof).unwrap() / x - 1.0).abs() < 1.0E-4)
+        }
+        // interval contains the estimate and widens with confidence
+        let (lo68, hi68) = confidence_interval(1.0, 50.0, 0.683).unwrap();
+        let (lo95, hi95) = confidence_interval(1.0, 50.0, 0.95).unwrap();
+        assert!(lo95 < lo68 && lo68 < 1.0 && 1.0 < hi68 && hi68 < hi95);
+        // asymmetric
+        assert!(hi95 - 1.0 > 1.0 - lo95);
+        // invalid degrees of freedom or levels
+        for dof in [-3600.0, 0.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(chi2_quantile(0.5, dof), Err(Error::InvalidDegreesOfFreedom)));
+            assert!(matches!(confidence_interval(1.0, dof, 0.95), Err(Error::InvalidDegreesOfFreedom)))
+        }
+        assert!(matches!(chi2_quantile(1.0, 10.0), Err(Error::InvalidConfidenceLevel)));
+        assert!(matches!(confidence_interval(1.0, 10.0, 0.0), Err(Error::InvalidConfidenceLevel)));
+    }
+    #[test]
+    fn test_bootstrap() {
+        use rand::{rngs::StdRng, SeedableRng};
+        use rand_distr::StandardNormal;
+        let mut rng = StdRng::seed_from_u64(0);
+        let data: Vec<f64> = (0..1000)
+            .map(|_| rng.sample(StandardNormal))
+            .collect();
+        // iid samples: mean estimate is normally distributed,
+        // sigma = 1 / sqrt(N)
+        let opts = Bootstrap {
+            iterations: 2000,
+            block_len: Some(1),
+            confidence: 0.95,
+        };
+        let mean = |series: &[Vec<f64>]| Ok(vec![statistical::mean(&series[0])]);
+        let ci = bootstrap(&[&data], true, &opts, &mut rng, mean).unwrap();
+        let m = statistical::mean(&data);
+        let sigma = 1.0 / 1000.0_f64.sqrt();
+        assert!((ci[0].0 - (m - 1.96 * sigma)).abs() < 0.2 * sigma);
+        assert!((ci[0].1 - (m + 1.96 * sigma)).abs() < 0.2 * sigma);
+        // reproducible
+        let ci_a = bootstrap(&[&data], true, &Bootstrap::default(), &mut StdRng::seed_from_u64(1), mean).unwrap();
+        let ci_b = bootstrap(&[&data], true, &Bootstrap::default(), &mut StdRng::seed_from_u64(1), mean).unwrap();
+        assert_eq!(ci_a, ci_b);
+        // invalid options
+        let opts = Bootstrap {
+            iterations: 0,
+            ..Bootstrap::default()
+        };
+        assert!(bootstrap(&[&data], true, &opts, &mut rng, mean).is_err());
+        assert!(bootstrap(&[&data, &data[1..]], true, &Bootstrap::default(), &mut rng, mean).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Delimited text files (CSV, TSV..) reader
+use super::{Error, Series, Unit};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Delimited text file parsing options
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// column delimiter, `None`: any whitespace
+    pub delimiter: Option<char>,
+    /// number of header lines to skip
+    pub skip_header: usize,
+    /// lines starting with this marker are ignored
+    pub comment: Option<char>,
+    /// data column index (starting at 0)
+    pub column: usize,
+    /// data column unit
+    pub unit: Unit,
+    /// optional timestamp column index (s)
+    pub timestamp: Option<usize>,
+}
+
+impl Default for Options {
+    /// Builds default options: comma separated values,   
+    /// no header, '#' comments, phase data (s) in first column
+    fn default() -> Options {
+        Options {
+            delimiter: Some(','),
+            skip_header: 0,
+            comment: Some('#'),
+            column: 0,
+            unit: Unit::Seconds,
+            timestamp: None,
+        }
+    }
+}
+
+/// Reads a delimited text file, refer to `parse`
+pub fn read<P: AsRef<Path>> (path: P, opts: &Options) -> Result<Series, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content, opts)
+}
+
+/// Parses delimited text content.   
+/// Empty lines and comments are ignored,   
+/// data are converted to seconds or fractional frequency.   
+/// content: file content   
+/// opts: parsing options
+pub fn parse (content: &str, opts: &Options) -> Result<Series, Error> {
+    let mut data: Vec<f64> = Vec::new();
+    let mut timestamps: Vec<f64> = Vec::new();
+    for (i, line) in content.lines().enumerate().skip(opts.skip_header) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue
+        }
+        if let Some(c) = opts.comment {
+            if line.starts_with(c) {
+                continue
+            }
+        }
+        let fields: Vec<&str> = match opts.delimiter {
+            Some(d) => line.split(d).map(|f| f.trim()).collect(),
+            None => line.split_whitespace().collect(),
+        };
+        data.push(opts.unit.convert(field(&fields, i+1, opts.column)?));
+        if let Some(col) = opts.timestamp {
+            timestamps.push(field(&fields, i+1, col)?)
+        }
+    }
+    if data.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    Ok(Series {
+        data,
+        is_fractional: opts.unit.is_fractional(),
+        timestamps: opts.timestamp.map(|_| timestamps),
+    })
+}
+
+/// Parses desired column, line is only used in error reports
+fn field (fields: &[&str], line: usize, column: usize) -> Result<f64, Error> {
+    let s = fields.get(column)
+        .ok_or(Error::MissingColumn(line, column))?;
+    f64::from_str(s)
+        .map_err(|_| Error::ParsingError(line, s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_csv_parser() {
+        let content = "time,phase_ns,freq_hz\n# comment\n0.0, 1.0, 10000000.1\n\n1.0, 2.5, 9999999.9\n2.0, -1.0, 10000000.0\n";
+        let opts = Options {
+            skip_header: 1,
+            column: 1,
+            unit: Unit::Nanoseconds,
+            timestamp: Some(0),
+            ..Default::default()
+        };
+        let series = parse(content, &opts).unwrap();
+        assert!(!series.is_fractional);
+        assert_eq!(series.data.len(), 3);
+        assert!((series.data[1] - 2.5E-9).abs() < 1.0E-20);
+        assert_eq!(series.timestamps, Some(vec![0.0, 1.0, 2.0]));
+        assert_eq!(series.sample_rate(), Some(1.0));
+
+        let opts = Options {
+            skip_header: 1,
+            column: 2,
+            unit: Unit::Hertz(10.0E6),
+            ..Default::default()
+        };
+        let series = parse(content, &opts).unwrap();
+        assert!(series.is_fractional);
+        assert!(series.timestamps.is_none());
+        assert!((series.data[0] - 1.0E-8).abs() < 1.0E-12);
+        assert!((series.data[1] + 1.0E-8).abs() < 1.0E-12);
+        // header is not skipped
+        let opts = Options {
+            column: 1,
+            ..Default::default()
+        };
+        assert!(matches!(parse(content, &opts), Err(Error::ParsingError(1, _))));
+        // missing column
+        let opts = Options {
+            skip_header: 1,
+            column: 3,
+            ..Default::default()
+        };
+        assert!(matches!(parse(content, &opts), Err(Error::MissingColumn(3, 3))));
+        // tab separated values
+        let content = "10\t-5.0\n20\t5.0\n";
+        let opts = Options {
+            delimiter: Some('\t'),
+            column: 1,
+            unit: Unit::PartsPerBillion,
+            timestamp: Some(0),
+            ..Default::default()
+        };
+        let series = parse(content, &opts).unwrap();
+        assert_eq!(series.data, vec![-5.0E-9, 5.0E-9]);
+        assert_eq!(series.sample_rate(), Some(0.1));
+        assert!(matches!(parse("# nothing\n", &opts), Err(Error::EmptyFile)));
+    }
+}
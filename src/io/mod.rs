@@ -0,0 +1,160 @@
+//! Readers for common clock & time transfer data files,
+//! producing series ready for `deviation()` and related statistics
+use thiserror::Error;
+use std::str::FromStr;
+
+pub mod csv;
+pub mod stable32;
+pub mod timelab;
+pub mod rinex;
+pub mod sp3;
+
+/// describes errors related to data file parsing
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("failed to read file")]
+    IoError(#[from] std::io::Error),
+    #[error("line {0}: missing column #{1}")]
+    MissingColumn(usize, usize),
+    #[error("line {0}: failed to parse \"{1}\"")]
+    ParsingError(usize, String),
+    #[error("file does not contain any data")]
+    EmptyFile,
+    #[error("missing header field: {0}")]
+    MissingHeaderField(String),
+    #[error("{0}: epoch MJD {1} does not lie on the {2} s sampling grid")]
+    OffGridEpoch(String, f64, f64),
+    #[error("{0}: several records at epoch MJD {1}")]
+    DuplicateEpoch(String, f64),
+    #[error("{0}: sampling grid of {1} epochs is too large")]
+    GridTooLarge(String, usize),
+}
+
+/// Describes units of a data column
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Unit {
+    /// phase / time error data (s)
+    #[default]
+    Seconds,
+    /// phase / time error data (ns)
+    Nanoseconds,
+    /// phase / time error data (ps)
+    Picoseconds,
+    /// fractional frequency data (n.a)
+    Fractional,
+    /// fractional frequency data (ppb)
+    PartsPerBillion,
+    /// absolute frequency data (Hz),   
+    /// converted to fractional frequency against given nominal frequency (Hz)
+    Hertz(f64),
+}
+
+impl Unit {
+    /// Returns true if this unit describes frequency data
+    pub fn is_fractional (&self) -> bool {
+        !matches!(self, Unit::Seconds | Unit::Nanoseconds | Unit::Picoseconds)
+    }
+
+    /// Converts given value to seconds (phase data)   
+    /// or fractional frequency (frequency data)
+    pub fn convert (&self, value: f64) -> f64 {
+        match self {
+            Unit::Seconds | Unit::Fractional => value,
+            Unit::Nanoseconds | Unit::PartsPerBillion => value * 1.0E-9_f64,
+            Unit::Picoseconds => value * 1.0E-12_f64,
+            Unit::Hertz(nominal) => (value - nominal) / nominal,
+        }
+    }
+}
+
+/// Data serie extracted from a file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    /// phase data (s) or fractional frequency data (n.a)
+    pub data: Vec<f64>,
+    /// true if `data` is fractional frequency data
+    pub is_fractional: bool,
+    /// sampling instants (s), when available
+    pub timestamps: Option<Vec<f64>>,
+}
+
+impl Series {
+    /// Returns sampling rate (Hz), deduced from   
+    /// mean sampling interval, when timestamps are available
+    pub fn sample_rate (&self) -> Option<f64> {
+        let t = self.timestamps.as_ref()?;
+        if t.len() < 2 {
+            return None
+        }
+        let dt = (t[t.len()-1] - t[0]) / (t.len() - 1) as f64;
+        match dt > 0.0_f64 {
+            true => Some(1.0_f64 / dt),
+            false => None,
+        }
+    }
+}
+
+/// Calendar epoch, as found in GNSS products
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Epoch {
+    /// year
+    pub year: i32,
+    /// month, in 1..=12
+    pub month: u32,
+    /// day of month, in 1..=31
+    pub day: u32,
+    /// hour, in 0..=23
+    pub hour: u32,
+    /// minute, in 0..=59
+    pub minute: u32,
+    /// second
+    pub second: f64,
+}
+
+impl Epoch {
+    /// Builds an epoch from year, month, day, hour, minute   
+    /// and second fields. Two digit years are   
+    /// mapped to 1980..=2079, like GNSS formats do
+    pub fn from_fields (fields: &[&str]) -> Option<Epoch> {
+        if fields.len() < 6 {
+            return None
+        }
+        let year = i32::from_str(fields[0]).ok()?;
+        let year = match year {
+            0..=79 => year + 2000,
+            80..=99 => year + 1900,
+            _ => year,
+        };
+        Some(Epoch {
+            year,
+            month: u32::from_str(fields[1]).ok()?,
+            day: u32::from_str(fields[2]).ok()?,
+            hour: u32::from_str(fields[3]).ok()?,
+            minute: u32::from_str(fields[4]).ok()?,
+            second: f64::from_str(fields[5]).ok()?,
+        })
+    }
+
+    /// Returns seconds elapsed since MJD 0 (1858-11-17)
+    pub fn seconds (&self) -> f64 {
+        // days from civil, refer to H. Hinnant
+        let y = match self.month <= 2 {
+            true => self.year as i64 - 1,
+            false => self.year as i64,
+        };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        // 1970-01-01 is MJD 40587
+        let mjd = era * 146097 + doe - 719468 + 40587;
+        (mjd * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60) as f64 + self.second
+    }
+
+    /// Returns Modified Julian Day
+    pub fn mjd (&self) -> f64 {
+        self.seconds() / 86400.0_f64
+    }
+}
+
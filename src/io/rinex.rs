@@ -0,0 +1,249 @@
+//! RINEX clock (.clk) files
+use super::{Epoch, Error, Series};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Largest resampling grid (epochs) we accept to allocate
+const MAX_GRID_SIZE: usize = 1 << 24;
+
+/// Largest distance to the sampling grid, as a fraction of `tau0`,   
+/// for an epoch to be considered on grid
+const GRID_TOLERANCE: f64 = 1.0E-3_f64;
+
+/// Describes a clock record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClockType {
+    /// satellite clock ("AS" records)
+    Satellite,
+    /// receiver / station clock ("AR" records)
+    Station,
+}
+
+/// Clock bias serie, extracted from a RINEX clock file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clock {
+    /// satellite or station name
+    pub name: String,
+    /// clock type
+    pub clock_type: ClockType,
+    /// first epoch
+    pub start: Epoch,
+    /// sampling interval (s)
+    pub tau0: f64,
+    /// clock bias, as phase data (s), on a regular `tau0` grid:   
+    /// missing epochs are marked with f64::NAN.   
+    /// timestamps are seconds elapsed since MJD 0
+    pub series: Series,
+}
+
+impl Clock {
+    /// Returns sampling rate (Hz)
+    pub fn sample_rate (&self) -> f64 {
+        1.0_f64 / self.tau0
+    }
+
+    /// Returns number of missing epochs.   
+    /// Series containing gaps should be analyzed   
+    /// with `Deviation::GapResistant`
+    pub fn gaps (&self) -> usize {
+        self.series.data.iter()
+            .filter(|x| x.is_nan())
+            .count()
+    }
+}
+
+/// Reads a RINEX clock file, refer to `parse`
+pub fn read<P: AsRef<Path>> (path: P) -> Result<Vec<Clock>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content)
+}
+
+/// Parses RINEX clock file content.   
+/// Satellite (AS) and station (AR) clock bias records are extracted,   
+/// other records are ignored.   
+/// Each clock is resampled on a regular grid, at its most common   
+/// epoch interval, gaps are marked with f64::NAN.   
+/// Epochs lying off that grid, or several records at the same epoch,   
+/// are reported as errors.   
+/// returns: clocks, sorted by type and name
+pub fn parse (content: &str) -> Result<Vec<Clock>, Error> {
+    let mut records: BTreeMap<(ClockType, String), Vec<(Epoch, f64)>> = BTreeMap::new();
+    let mut lines = content.lines().enumerate();
+    for (_, line) in lines.by_ref() {
+        if line.contains("END OF HEADER") {
+            break
+        }
+    }
+    for (i, line) in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let clock_type = match fields.first() {
+            Some(&"AS") => ClockType::Satellite,
+            Some(&"AR") => ClockType::Station,
+            _ => continue,
+        };
+        if fields.len() < 10 {
+            return Err(Error::ParsingError(i+1, line.trim().to_string()))
+        }
+        let epoch = Epoch::from_fields(&fields[2..8])
+            .ok_or_else(|| Error::ParsingError(i+1, line.trim().to_string()))?;
+        let bias = f64::from_str(&fields[9].replace(['D', 'd'], "E"))
+            .map_err(|_| Error::ParsingError(i+1, fields[9].to_string()))?;
+        records.entry((clock_type, fields[1].to_string()))
+            .or_default()
+            .push((epoch, bias))
+    }
+    if records.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    records.into_iter()
+        .map(|((clock_type, name), records)| regrid(name, clock_type, records, None))
+        .collect()
+}
+
+/// Builds a clock from (epoch, bias) records, on a regular grid:   
+/// missing epochs are marked with f64::NAN.   
+/// The grid interval is `interval` (s) when known (file header),   
+/// the most common epoch interval otherwise
+pub(super) fn regrid (name: String, clock_type: ClockType, mut records: Vec<(Epoch, f64)>, interval: Option<f64>) -> Result<Clock, Error> {
+    records.sort_by(|a, b| a.0.seconds().total_cmp(&b.0.seconds()));
+    let t0 = records[0].0.seconds();
+    let tau0 = match interval {
+        Some(dt) if dt > 0.0_f64 => dt,
+        _ => {
+            // intervals are counted at µs resolution,
+            // ties resolve to the shortest interval
+            let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+            for w in records.windows(2) {
+                let dt = ((w[1].0.seconds() - w[0].0.seconds()) * 1.0E6_f64).round() as i64;
+                if dt > 0 {
+                    *counts.entry(dt).or_default() += 1
+                }
+            }
+            counts.iter()
+                .rev()
+                .max_by_key(|(_, count)| **count)
+                .map(|(dt, _)| *dt as f64 * 1.0E-6_f64)
+                .unwrap_or(1.0_f64)
+        },
+    };
+    let last = records[records.len()-1].0.seconds();
+    let span = ((last - t0) / tau0).round();
+    if span >= MAX_GRID_SIZE as f64 {
+        return Err(Error::GridTooLarge(name, span as usize + 1))
+    }
+    let size = span as usize + 1;
+    let mut data = vec![f64::NAN; size];
+    let mut filled = vec![false; size];
+    for (epoch, bias) in &records {
+        let offset = (epoch.seconds() - t0) / tau0;
+        let k = offset.round();
+        if (offset - k).abs() > GRID_TOLERANCE {
+            return Err(Error::OffGridEpoch(name, epoch.mjd(), tau0))
+        }
+        let k = k as usize;
+        if filled[k] {
+            return Err(Error::DuplicateEpoch(name, epoch.mjd()))
+        }
+        filled[k] = true;
+        data[k] = *bias
+    }
+    Ok(Clock {
+        name,
+        clock_type,
+        start: records[0].0,
+        tau0,
+        series: Series {
+            data,
+            is_fractional: false,
+            timestamps: Some((0..size).map(|k| t0 + k as f64 * tau0).collect()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_rinex_clock() {
+        let content = "     3.00           C                                       RINEX VERSION / TYPE
+     1    AS                                                    # OF CLK DATA TYPES
+                                                            END OF HEADER
+AR ALGO 2020 01 01 00 00  0.000000  1    1.000000000000E-08
+AS G01  2020 01 01 00 00  0.000000  2    1.000000000000E-04  1.0E-11
+AS G02  2020 01 01 00 00  0.000000  2   -2.000000000000E-04  1.0E-11
+AS G01  2020 01 01 00 00 30.000000  2    1.000000001000E-04  1.0E-11
+AS G01  2020 01 01 00 01 30.000000  2    1.000000003000E-04  1.0E-11
+AR ALGO 2020 01 01 00 05  0.000000  1    1.100000000000E-08
+CR ALGO 2020 01 01 00 05  0.000000  1    1.100000000000E-08
+";
+        let clocks = parse(content).unwrap();
+        assert_eq!(clocks.len(), 3);
+        let g01 = &clocks[0];
+        assert_eq!(g01.name, "G01");
+        assert_eq!(g01.clock_type, ClockType::Satellite);
+        assert_eq!(g01.tau0, 30.0);
+        assert_eq!(g01.series.data.len(), 4);
+        assert!(g01.series.data[2].is_nan());
+        assert_eq!(g01.gaps(), 1);
+        assert_eq!(g01.series.data[3], 1.000000003000E-04);
+        assert_eq!(g01.start.mjd(), 58849.0);
+        let g02 = &clocks[1];
+        assert_eq!(g02.series.data, vec![-2.0E-4]);
+        let algo = &clocks[2];
+        assert_eq!(algo.clock_type, ClockType::Station);
+        assert_eq!(algo.tau0, 300.0);
+        assert_eq!(algo.sample_rate(), 1.0 / 300.0);
+        assert_eq!(algo.gaps(), 0);
+        assert!(matches!(parse("END OF HEADER\nAS G01 2020 01\n"), Err(Error::ParsingError(2, _))));
+        assert!(matches!(parse("END OF HEADER\n"), Err(Error::EmptyFile)));
+    }
+    #[test]
+    fn test_rinex_resampling() {
+        let epoch = |minute: u32, second: f64| Epoch {
+            year: 2020,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute,
+            second,
+        };
+        // single short interval does not drive the grid
+        let records = vec![
+            (epoch(0, 0.0), 1.0),
+            (epoch(0, 30.0), 2.0),
+            (epoch(1, 0.0), 3.0),
+            (epoch(1, 30.0), 4.0),
+            (epoch(3, 0.0), 5.0),
+        ];
+        let clock = regrid("G01".to_string(), ClockType::Satellite, records.clone(), None).unwrap();
+        assert_eq!(clock.tau0, 30.0);
+        assert_eq!(clock.series.data.len(), 7);
+        assert_eq!(clock.gaps(), 2);
+        // known interval
+        let clock = regrid("G01".to_string(), ClockType::Satellite, records.clone(), Some(15.0)).unwrap();
+        assert_eq!(clock.tau0, 15.0);
+        assert_eq!(clock.series.data.len(), 13);
+        // off grid
+        let mut off_grid = records.clone();
+        off_grid.push((epoch(3, 10.0), 6.0));
+        assert!(matches!(
+            regrid("G01".to_string(), ClockType::Satellite, off_grid, None),
+            Err(Error::OffGridEpoch(_, _, _))));
+        // collision
+        let mut duplicate = records.clone();
+        duplicate.push((epoch(1, 0.0), 3.0));
+        assert!(matches!(
+            regrid("G01".to_string(), ClockType::Satellite, duplicate, None),
+            Err(Error::DuplicateEpoch(_, _))));
+        // sparse
+        let sparse = vec![
+            (epoch(0, 0.0), 1.0),
+            (epoch(0, 0.001), 2.0),
+            (Epoch { year: 2021, ..epoch(0, 0.0) }, 3.0),
+        ];
+        assert!(matches!(
+            regrid("G01".to_string(), ClockType::Satellite, sparse, None),
+            Err(Error::GridTooLarge(_, _))));
+    }
+}
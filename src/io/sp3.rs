@@ -0,0 +1,129 @@
+//! SP3 precise orbit files, satellite clock corrections
+use super::{rinex, Epoch, Error};
+use super::rinex::{Clock, ClockType};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// SP3 bad or absent clock value marker (µs)
+const BAD_CLOCK_VALUE: f64 = 999999.0_f64;
+
+/// Reads an SP3 file, refer to `parse`
+pub fn read<P: AsRef<Path>> (path: P) -> Result<Vec<Clock>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content)
+}
+
+/// Parses SP3 file content and extracts satellite clock corrections   
+/// from position (P) records.   
+/// Clock corrections are converted from µs to phase data (s),   
+/// bad values (999999.999999) and missing epochs   
+/// are marked with f64::NAN.   
+/// Clocks are resampled at the header epoch interval, refer to `rinex::parse`.   
+/// returns: satellite clocks, sorted by name
+pub fn parse (content: &str) -> Result<Vec<Clock>, Error> {
+    let mut records: BTreeMap<String, Vec<(Epoch, f64)>> = BTreeMap::new();
+    let mut epoch: Option<Epoch> = None;
+    let mut interval: Option<f64> = None;
+    for (i, line) in content.lines().enumerate() {
+        // epoch interval is the third field of the second header line
+        if let Some(rem) = line.strip_prefix("##") {
+            interval = rem.split_whitespace()
+                .nth(2)
+                .and_then(|f| f64::from_str(f).ok());
+            continue
+        }
+        if let Some(rem) = line.strip_prefix('*') {
+            let fields: Vec<&str> = rem.split_whitespace().collect();
+            epoch = Some(Epoch::from_fields(&fields)
+                .ok_or_else(|| Error::ParsingError(i+1, line.trim().to_string()))?);
+            continue
+        }
+        if !line.starts_with('P') {
+            continue
+        }
+        let epoch = epoch
+            .ok_or_else(|| Error::ParsingError(i+1, line.trim().to_string()))?;
+        let name = line.get(1..4)
+            .ok_or_else(|| Error::ParsingError(i+1, line.trim().to_string()))?
+            .trim()
+            .to_string();
+        // clock correction lies in columns 47-60
+        let clock = match line.get(46..60).map(|f| f.trim()) {
+            Some(f) if !f.is_empty() => f64::from_str(f)
+                .map_err(|_| Error::ParsingError(i+1, f.to_string()))?,
+            _ => BAD_CLOCK_VALUE,
+        };
+        let bias = match clock.abs() >= BAD_CLOCK_VALUE {
+            true => f64::NAN,
+            false => clock * 1.0E-6_f64,
+        };
+        records.entry(name)
+            .or_default()
+            .push((epoch, bias))
+    }
+    if records.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    records.into_iter()
+        .map(|(name, records)| rinex::regrid(name, ClockType::Satellite, records, interval))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_sp3_clocks() {
+        let content = "#dP2020  1  1  0  0  0.00000000       4 ORBIT IGS14 HLM  IGS
+## 2087 259200.00000000   900.00000000 58849 0.0000000000000
++    2   G01G02  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+/* comment
+*  2020  1  1  0  0  0.00000000
+PG01  15000.000000 -20000.000000   5000.000000    -12.500000
+PG02 -15000.000000  20000.000000  -5000.000000 999999.999999
+*  2020  1  1  0 15  0.00000000
+PG01  15001.000000 -20001.000000   5001.000000    -12.500100
+PG02 -15001.000000  20001.000000  -5001.000000    300.000000
+*  2020  1  1  0 30  0.00000000
+PG02 -15002.000000  20002.000000  -5002.000000    300.000200
+*  2020  1  1  0 45  0.00000000
+PG01  15003.000000 -20003.000000   5003.000000    -12.500300
+EOF
+";
+        let clocks = parse(content).unwrap();
+        assert_eq!(clocks.len(), 2);
+        let g01 = &clocks[0];
+        assert_eq!(g01.name, "G01");
+        assert_eq!(g01.clock_type, ClockType::Satellite);
+        assert_eq!(g01.tau0, 900.0);
+        assert_eq!(g01.series.data.len(), 4);
+        assert!((g01.series.data[0] + 12.5E-6).abs() < 1.0E-18);
+        assert!((g01.series.data[3] + 12.5003E-6).abs() < 1.0E-18);
+        assert!(g01.series.data[2].is_nan());
+        let g02 = &clocks[1];
+        assert_eq!(g02.series.data.len(), 3);
+        assert_eq!(g02.gaps(), 1);
+        assert!(g02.series.data[0].is_nan());
+        assert!((g02.series.data[2] - 300.0002E-6).abs() < 1.0E-18);
+        assert!(matches!(parse("PG01  1.0 2.0 3.0 4.0\n"), Err(Error::ParsingError(1, _))));
+        assert!(matches!(parse("#dP2020\nEOF\n"), Err(Error::EmptyFile)));
+        // header interval drives the grid
+        let content = "## 2087 259200.00000000   300.00000000 58849 0.0000000000000
+*  2020  1  1  0  0  0.00000000
+PG01  15000.000000 -20000.000000   5000.000000    -12.500000
+*  2020  1  1  0 15  0.00000000
+PG01  15001.000000 -20001.000000   5001.000000    -12.500100
+";
+        let clocks = parse(content).unwrap();
+        assert_eq!(clocks[0].tau0, 300.0);
+        assert_eq!(clocks[0].series.data.len(), 4);
+        let content = "## 2087 259200.00000000   900.00000000 58849 0.0000000000000
+*  2020  1  1  0  0  0.00000000
+PG01  15000.000000 -20000.000000   5000.000000    -12.500000
+*  2020  1  1  0 10  0.00000000
+PG01  15001.000000 -20001.000000   5001.000000    -12.500100
+";
+        assert!(matches!(parse(content), Err(Error::OffGridEpoch(_, _, _))));
+    }
+}
@@ -0,0 +1,306 @@
+//! Stable32 data & Sigma-Tau results files
+use super::{Error, Series, Unit};
+use crate::{confidence, tau, deviation_edf, Deviation};
+use std::path::Path;
+use std::str::FromStr;
+
+/// seconds per day, Stable32 timestamps are MJD
+const SECONDS_PER_DAY: f64 = 86400.0_f64;
+
+/// Reads a Stable32 data file, refer to `parse`
+pub fn read<P: AsRef<Path>> (path: P, unit: Unit) -> Result<Series, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content, unit)
+}
+
+/// Parses Stable32 phase or frequency data file content.   
+/// Files either contain one value per line,   
+/// or MJD timestamp and value columns.   
+/// Non numerical lines (headers) are ignored until data starts,   
+/// malformed data lines are reported as errors.   
+/// content: file content   
+/// unit: data unit   
+/// returns: data converted to seconds or fractional frequency,   
+/// timestamps converted to seconds
+pub fn parse (content: &str, unit: Unit) -> Result<Series, Error> {
+    let mut data: Vec<f64> = Vec::new();
+    let mut timestamps: Vec<f64> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|f| !f.is_empty())
+            .collect();
+        let values: Vec<f64> = match fields.iter().map(|f| f64::from_str(f)).collect() {
+            Ok(values) => values,
+            Err(_) if data.is_empty() => continue,
+            Err(_) => return Err(Error::ParsingError(i+1, line.trim().to_string())),
+        };
+        match values.len() {
+            0 => continue,
+            1 => data.push(unit.convert(values[0])),
+            2 => {
+                timestamps.push(values[0] * SECONDS_PER_DAY);
+                data.push(unit.convert(values[1]))
+            },
+            _ => return Err(Error::ParsingError(i+1, line.trim().to_string())),
+        }
+    }
+    if data.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    let timestamps = match timestamps.len() == data.len() {
+        true => Some(timestamps),
+        false => None,
+    };
+    Ok(Series {
+        data,
+        is_fractional: unit.is_fractional(),
+        timestamps,
+    })
+}
+
+/// Formats a serie as Stable32 data file content:   
+/// one value per line, preceded by MJD timestamp when available
+pub fn format (series: &Series) -> String {
+    let mut content = String::new();
+    for (i, x) in series.data.iter().enumerate() {
+        match series.timestamps.as_ref().and_then(|t| t.get(i)) {
+            Some(t) => content.push_str(&format!("{:.8} {:.12e}\n", t / SECONDS_PER_DAY, x)),
+            None => content.push_str(&format!("{:.12e}\n", x)),
+        }
+    }
+    content
+}
+
+/// Writes a serie as Stable32 data file, refer to `format`
+pub fn write<P: AsRef<Path>> (path: P, series: &Series) -> Result<(), Error> {
+    std::fs::write(path, format(series))?;
+    Ok(())
+}
+
+/// Stable32 Sigma-Tau table entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    /// averaging time (s)
+    pub tau: f64,
+    /// number of analysis points
+    pub points: usize,
+    /// identified noise process:   
+    /// fractional frequency PSD slope
+    pub alpha: i32,
+    /// lower confidence bound
+    pub min_sigma: f64,
+    /// nominal deviation
+    pub sigma: f64,
+    /// upper confidence bound
+    pub max_sigma: f64,
+}
+
+/// Computes desired deviation and builds a Stable32 Sigma-Tau table.   
+/// data: input vector   
+/// taus: desired `tau` offsets (s)   
+/// calc: desired deviation   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// confidence: confidence level of min/max sigma,   
+/// Stable32 defaults to 0.683   
+/// returns: one entry per feasible `tau`, up to the first `tau`   
+/// whose degrees of freedom are not usable
+#[allow(clippy::too_many_arguments)]
+pub fn results (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool,
+        confidence: f64) -> Result<Vec<Record>, crate::Error>
+{
+    // noise processes are the ones the bounds rely on
+    let (devs, edfs, alphas, sizes) = deviation_edf(data, taus, calc, sample_rate, is_fractional, overlapping)?;
+    let mut records: Vec<Record> = Vec::with_capacity(devs.len());
+    for i in 0..devs.len() {
+        let (min_sigma, max_sigma) = match confidence::confidence_interval(devs[i], edfs[i], confidence) {
+            Ok(interval) => interval,
+            Err(crate::Error::InvalidDegreesOfFreedom) => break,
+            Err(e) => return Err(e),
+        };
+        records.push(Record {
+            tau: taus[i],
+            // gaps excluded, fractional data are integrated to as many phase points
+            points: analysis_points(calc, sizes[i], taus[i] * sample_rate, overlapping),
+            alpha: alphas[i],
+            min_sigma,
+            sigma: devs[i],
+            max_sigma,
+        })
+    }
+    Ok(records)
+}
+
+/// Returns number of analysis points (terms of the estimator)   
+/// for `n` (equivalent) phase points, at averaging time `tau_u` (samples)
+fn analysis_points (calc: Deviation, n: usize, tau_u: f64, overlapping: bool) -> usize {
+    if let Deviation::Theo1 | Deviation::TheoBR | Deviation::TheoH = calc {
+        if tau::is_theo_multiple(tau_u) {
+            return n.saturating_sub((tau_u / 0.75_f64).round() as usize)
+        }
+    }
+    // same truncation as the estimators
+    let m = tau_u as usize;
+    // non overlapping terms, each spanning `span` averaging intervals
+    let strided = |span: usize| -> usize {
+        n.saturating_sub(1)
+            .checked_div(m)
+            .map(|k| (k + 1).saturating_sub(span))
+            .unwrap_or(0)
+    };
+    match calc {
+        Deviation::Allan | Deviation::Parabolic | Deviation::GapResistant | Deviation::TheoH => match overlapping {
+            true => n.saturating_sub(2*m),
+            false => strided(2),
+        },
+        Deviation::Hadamard | Deviation::HadamardTotal => match overlapping {
+            true => n.saturating_sub(3*m),
+            false => strided(3),
+        },
+        Deviation::Modified | Deviation::Time | Deviation::ModifiedTotal | Deviation::TimeTotal =>
+            (n+1).saturating_sub(3*m),
+        Deviation::ModifiedHadamard => (n+1).saturating_sub(4*m),
+        Deviation::Total | Deviation::Theo1 | Deviation::TheoBR => n.saturating_sub(2),
+    }
+}
+
+/// Formats a Sigma-Tau table, in Stable32 layout
+pub fn format_results (records: &[Record]) -> String {
+    let mut content = format!("#{:>14} {:>8} {:>6} {:>14} {:>14} {:>14}\n",
+        "Tau", "#", "Alpha", "Min Sigma", "Sigma", "Max Sigma");
+    for r in records {
+        content.push_str(&format!("{:>15.6e} {:>8} {:>6} {:>14.6e} {:>14.6e} {:>14.6e}\n",
+            r.tau, r.points, r.alpha, r.min_sigma, r.sigma, r.max_sigma))
+    }
+    content
+}
+
+/// Writes a Sigma-Tau table, refer to `format_results`
+pub fn write_results<P: AsRef<Path>> (path: P, records: &[Record]) -> Result<(), Error> {
+    std::fs::write(path, format_results(records))?;
+    Ok(())
+}
+
+/// Reads a Stable32 Sigma-Tau table, refer to `parse_results`
+pub fn read_results<P: AsRef<Path>> (path: P) -> Result<Vec<Record>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse_results(&content)
+}
+
+/// Parses Stable32 Sigma-Tau table content,   
+/// to compare against our own results.   
+/// Non numerical lines (headers) are ignored.
+pub fn parse_results (content: &str) -> Result<Vec<Record>, Error> {
+    let mut records: Vec<Record> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let values: Vec<f64> = match fields.iter().map(|f| f64::from_str(f)).collect() {
+            Ok(values) => values,
+            Err(_) => continue,
+        };
+        match values.len() {
+            0 => continue,
+            6 => records.push(Record {
+                tau: values[0],
+                points: values[1] as usize,
+                alpha: values[2].round() as i32,
+                min_sigma: values[3],
+                sigma: values[4],
+                max_sigma: values[5],
+            }),
+            _ => return Err(Error::ParsingError(i+1, line.trim().to_string())),
+        }
+    }
+    if records.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+    use rand::{rngs::StdRng, SeedableRng};
+    #[test]
+    fn test_stable32_data() {
+        let content = "Stable32 phase data\n1.0\n2.0\n\n-3.5\n";
+        let series = parse(content, Unit::Nanoseconds).unwrap();
+        assert_eq!(series.data.len(), 3);
+        assert!((series.data[2] + 3.5E-9).abs() < 1.0E-20);
+        assert!(series.timestamps.is_none());
+        let content = "59000.0 1.0E-12\n59000.5 2.0E-12\n59001.0 -1.0E-12\n";
+        let series = parse(content, Unit::Fractional).unwrap();
+        assert!(series.is_fractional);
+        assert_eq!(series.sample_rate(), Some(1.0 / 43200.0));
+        // round trip
+        let parsed = parse(&format(&series), Unit::Fractional).unwrap();
+        for (a, b) in parsed.data.iter().zip(series.data.iter()) {
+            assert!((a - b).abs() < 1.0E-24)
+        }
+        assert_eq!(parsed.timestamps, series.timestamps);
+        assert!(matches!(parse("1.0 2.0 3.0\n", Unit::Seconds), Err(Error::ParsingError(1, _))));
+        assert!(matches!(parse("header\n", Unit::Seconds), Err(Error::EmptyFile)));
+        // corrupt data line
+        assert!(matches!(parse("header\n1.0\n2.O\n3.0\n", Unit::Seconds), Err(Error::ParsingError(3, _))));
+    }
+    #[test]
+    fn test_stable32_results() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let data = utils::random_with_rng(4096, &mut rng);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 1024.0_f64);
+        let records = results(&data, &taus, Deviation::Allan, 1.0_f64, false, true, 0.683_f64)
+            .unwrap();
+        let (adev, _) = crate::deviation(&data, &taus, Deviation::Allan, 1.0_f64, false, true)
+            .unwrap();
+        assert_eq!(records.len(), adev.len());
+        for (r, dev) in records.iter().zip(adev.iter()) {
+            assert_eq!(r.sigma, *dev);
+            assert!(r.min_sigma < r.sigma && r.sigma < r.max_sigma);
+            assert_eq!(r.points, 4096 - 2 * r.tau as usize)
+        }
+        // white PM
+        assert_eq!(records[0].alpha, 2);
+        // round trip
+        let parsed = parse_results(&format_results(&records)).unwrap();
+        assert_eq!(parsed.len(), records.len());
+        for (a, b) in parsed.iter().zip(records.iter()) {
+            assert_eq!(a.points, b.points);
+            assert_eq!(a.alpha, b.alpha);
+            assert!((a.sigma / b.sigma - 1.0).abs() < 1.0E-6)
+        }
+    }
+    #[test]
+    fn test_stable32_analysis_points() {
+        // sub sample tau has no terms
+        assert_eq!(analysis_points(Deviation::Allan, 100, 0.25_f64, false), 0);
+        assert_eq!(analysis_points(Deviation::Hadamard, 100, 0.5_f64, true), 100);
+        assert_eq!(analysis_points(Deviation::Allan, 100, 1.0_f64, false), 98);
+        assert_eq!(analysis_points(Deviation::Hadamard, 100, 10.0_f64, false), 7);
+        assert_eq!(analysis_points(Deviation::Allan, 100, 60.0_f64, true), 0);
+        // fractional data: as many phase points as samples
+        let mut rng = StdRng::seed_from_u64(0);
+        let data = utils::random_with_rng(100, &mut rng);
+        let records = results(&data, &[1.0_f64, 4.0_f64], Deviation::Allan, 1.0_f64, true, false, 0.683_f64)
+            .unwrap();
+        assert_eq!(records[0].points, 98);
+        assert_eq!(records[1].points, 23);
+        // gaps: valid terms only
+        let mut data = utils::random_with_rng(1000, &mut rng);
+        for i in (100..1000).step_by(97) {
+            data[i] = f64::NAN
+        }
+        let taus = [1.0_f64, 2.0_f64, 8.0_f64];
+        for overlapping in [true, false] {
+            let records = results(&data, &taus, Deviation::GapResistant, 1.0_f64, false, overlapping, 0.683_f64)
+                .unwrap();
+            for r in &records {
+                let (_, _, n) = crate::calc_gradev(&data, r.tau, 1.0_f64, false, overlapping).unwrap();
+                assert_eq!(r.points, n);
+                assert!(r.points < analysis_points(Deviation::GapResistant, data.len(), r.tau, overlapping))
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! TimeLab .tim measurement files
+use super::{Error, Series, Unit};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Describes the content of a TimeLab trace
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TraceType {
+    /// phase difference data (s)
+    #[default]
+    Phase,
+    /// fractional frequency difference data (n.a)
+    FractionalFrequency,
+    /// absolute frequency data (Hz)
+    Frequency,
+}
+
+/// TimeLab capture header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header {
+    /// nominal input frequency (Hz)
+    pub input_frequency: Option<f64>,
+    /// sampling interval (s)
+    pub sample_interval: Option<f64>,
+    /// trace type
+    pub trace_type: TraceType,
+    /// all header fields, as (key, value), in order of appearance
+    pub fields: Vec<(String, String)>,
+}
+
+impl Header {
+    /// Returns value of first header field whose key contains `key`,   
+    /// case insensitive
+    pub fn get (&self, key: &str) -> Option<&str> {
+        let key = key.to_lowercase();
+        self.fields.iter()
+            .find(|(k, _)| k.to_lowercase().contains(&key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// TimeLab capture
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Capture {
+    /// header metadata
+    pub header: Header,
+    /// phase data (s) or fractional frequency data (n.a)
+    pub series: Series,
+}
+
+impl Capture {
+    /// Returns sampling rate (Hz), from header sampling interval,   
+    /// or deduced from timestamps
+    pub fn sample_rate (&self) -> Option<f64> {
+        match self.header.sample_interval {
+            Some(dt) if dt > 0.0_f64 => Some(1.0_f64 / dt),
+            _ => self.series.sample_rate(),
+        }
+    }
+}
+
+/// Reads a TimeLab .tim file, refer to `parse`
+pub fn read<P: AsRef<Path>> (path: P) -> Result<Capture, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content)
+}
+
+/// Parses TimeLab .tim file content.   
+/// Header lines are "key: value" or "key = value" pairs,   
+/// unknown fields and free text are tolerated.   
+/// Data lines contain one value, or timestamp (s) and value columns.   
+/// Frequency traces are converted to fractional frequency,   
+/// against the input frequency.
+pub fn parse (content: &str) -> Result<Capture, Error> {
+    let mut header = Header::default();
+    let mut values: Vec<f64> = Vec::new();
+    let mut timestamps: Vec<f64> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|f| !f.is_empty())
+            .collect();
+        let numbers: Result<Vec<f64>, _> = fields.iter().map(|f| f64::from_str(f)).collect();
+        match numbers {
+            Ok(numbers) if numbers.len() == 1 => values.push(numbers[0]),
+            Ok(numbers) if numbers.len() == 2 => {
+                timestamps.push(numbers[0]);
+                values.push(numbers[1])
+            },
+            _ => {
+                if !values.is_empty() {
+                    return Err(Error::ParsingError(i+1, line.to_string()))
+                }
+                if let Some(pos) = line.find([':', '=']) {
+                    let (key, value) = line.split_at(pos);
+                    header.fields.push((key.trim().to_string(), value[1..].trim().to_string()))
+                }
+            },
+        }
+    }
+    if values.is_empty() {
+        return Err(Error::EmptyFile)
+    }
+    header.input_frequency = header.get("input freq")
+        .or_else(|| header.get("nominal freq"))
+        .and_then(parse_quantity);
+    header.sample_interval = header.get("sample interval")
+        .or_else(|| header.get("tau0"))
+        .or_else(|| header.get("tau 0"))
+        .and_then(parse_quantity);
+    if let Some(t) = header.get("trace type").or_else(|| header.get("data type")) {
+        let t = t.to_lowercase();
+        header.trace_type = if t.contains("fractional") {
+            TraceType::FractionalFrequency
+        } else if t.contains("freq") && !t.contains("phase") {
+            TraceType::Frequency
+        } else {
+            TraceType::Phase
+        }
+    }
+    let unit = match header.trace_type {
+        TraceType::Phase => Unit::Seconds,
+        TraceType::FractionalFrequency => Unit::Fractional,
+        TraceType::Frequency => {
+            let f0 = header.input_frequency
+                .ok_or_else(|| Error::MissingHeaderField("input frequency".to_string()))?;
+            Unit::Hertz(f0)
+        },
+    };
+    let timestamps = match timestamps.len() == values.len() {
+        true => Some(timestamps),
+        false => None,
+    };
+    Ok(Capture {
+        header,
+        series: Series {
+            data: values.iter().map(|x| unit.convert(*x)).collect(),
+            is_fractional: unit.is_fractional(),
+            timestamps,
+        },
+    })
+}
+
+/// Parses a physical quantity, like "10 MHz", "1.0E+000 s", "100ms" or "1 min".   
+/// The unit must be one of s, ms, us, µs, ns, ps, min, h, d, Hz, kHz, MHz, GHz,   
+/// or absent: unknown units are not interpreted
+fn parse_quantity (value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value
+        .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace() || c == 'µ');
+    let scaling = match value[number.len()..].trim() {
+        "" | "s" | "Hz" => 1.0_f64,
+        "ms" => 1.0E-3_f64,
+        "us" | "µs" => 1.0E-6_f64,
+        "ns" => 1.0E-9_f64,
+        "ps" => 1.0E-12_f64,
+        "min" => 60.0_f64,
+        "h" => 3600.0_f64,
+        "d" => 86400.0_f64,
+        "kHz" => 1.0E3_f64,
+        "MHz" => 1.0E6_f64,
+        "GHz" => 1.0E9_f64,
+        _ => return None,
+    };
+    f64::from_str(number.trim())
+        .ok()
+        .map(|x| x * scaling)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_timelab_parser() {
+        let content = "TimeLab .TIM file\n\
+            Trace: GPSDO vs Maser\n\
+            Input freq = 10 MHz\n\
+            Sample interval = 1.0E-001 s\n\
+            Trace type: Phase difference\n\
+            Begin data\n\
+            1.0E-9\n\
+            1.5E-9\n\
+            \n\
+            1.2E-9\n";
+        let capture = parse(content).unwrap();
+        assert_eq!(capture.header.input_frequency, Some(10.0E6));
+        assert_eq!(capture.header.sample_interval, Some(0.1));
+        assert_eq!(capture.header.trace_type, TraceType::Phase);
+        assert_eq!(capture.header.get("TRACE"), Some("GPSDO vs Maser"));
+        assert_eq!(capture.sample_rate(), Some(10.0));
+        assert!(!capture.series.is_fractional);
+        assert_eq!(capture.series.data, vec![1.0E-9, 1.5E-9, 1.2E-9]);
+        assert!(capture.series.timestamps.is_none());
+
+        let content = "Data type: Fractional frequency\n-1.0E-12\n";
+        assert_eq!(parse(content).unwrap().header.trace_type, TraceType::FractionalFrequency);
+        let content = "Data type: Frequency\nInput freq: 5000000.0 Hz\n0.0 5000000.005\n100.0 4999999.995\n";
+        let capture = parse(content).unwrap();
+        assert_eq!(capture.header.trace_type, TraceType::Frequency);
+        assert!(capture.series.is_fractional);
+        assert!((capture.series.data[0] - 1.0E-9).abs() < 1.0E-15);
+        assert!((capture.series.data[1] + 1.0E-9).abs() < 1.0E-15);
+        assert_eq!(capture.sample_rate(), Some(0.01));
+        assert!(matches!(parse("Data type: Frequency\n1.0\n"), Err(Error::MissingHeaderField(_))));
+        assert!(matches!(parse("1.0\ncorrupted\n"), Err(Error::ParsingError(2, _))));
+        assert!(matches!(parse("Trace type: Phase\n"), Err(Error::EmptyFile)));
+        assert_eq!(parse_quantity("100ms"), Some(0.1));
+        assert_eq!(parse_quantity("10 min"), Some(600.0));
+        assert_eq!(parse_quantity("1 h"), Some(3600.0));
+        assert_eq!(parse_quantity("1 d"), Some(86400.0));
+        assert_eq!(parse_quantity("2.5 GHz"), Some(2.5E9));
+        assert_eq!(parse_quantity("1.0E+000 s"), Some(1.0));
+        assert_eq!(parse_quantity("250 µs"), Some(250.0E-6));
+        assert_eq!(parse_quantity("5"), Some(5.0));
+        assert_eq!(parse_quantity("10 mins"), None);
+        assert_eq!(parse_quantity("1 hour"), None);
+    }
+}
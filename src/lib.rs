@@ -4,8 +4,8 @@
 //! Allantools (python) library to compute Allan & related
 //! statistics over some data.
 //!
-//! This lib only implements basic (biased) 
-//! error bar estimates at the moment.
+//! Error bars are derived from the equivalent degrees
+//! of freedom of each estimate, refer to `confidence`.
 //!
 //! url: <https://github.com/gwbres/allan-tools>  
 //! url: <https://github.com/aewallin/allantools>
@@ -13,8 +13,13 @@
 pub mod tau;
 pub mod noise;
 pub mod utils;
+pub mod time_error;
+pub mod confidence;
+pub mod io;
 
 use thiserror::Error;
+use std::collections::VecDeque;
+use rand::Rng;
 
 /// describes error related to deviation computations
 #[derive(Error, Debug)]
@@ -23,6 +28,20 @@ pub enum Error {
     TauAxisEror(#[from] tau::Error), 
     #[error("non feasible deviation - missing some more samples")]
     NotEnoughSamplesError, 
+    #[error("input series should have the same length")]
+    SeriesLengthMismatch,
+    #[error("sliding window length and step should not be null")]
+    InvalidWindowError,
+    #[error("confidence level should lie within ]0, 1[")]
+    InvalidConfidenceLevel,
+    #[error("equivalent degrees of freedom should be finite and positive")]
+    InvalidDegreesOfFreedom,
+    #[error("bootstrap iterations and block length should not be null")]
+    InvalidBootstrapError,
+    #[error("phase noise profile should comprise increasing, positive offset frequencies")]
+    InvalidPhaseNoiseProfile,
+    #[error("correlation matrix should be square, symmetric and positive definite")]
+    InvalidCorrelationMatrix,
 }
 
 #[derive(Clone, Copy)]
@@ -36,6 +55,31 @@ pub enum Deviation {
     Time,     
     /// `hadamard` deviation
     Hadamard,
+    /// `total` deviation
+    Total,
+    /// `modified total` deviation
+    ModifiedTotal,
+    /// `time total` deviation
+    TimeTotal,
+    /// `hadamard total` deviation
+    HadamardTotal,
+    /// `modified hadamard` deviation
+    ModifiedHadamard,
+    /// `theo1` deviation,
+    /// evaluated at tau = 0.75 * m * tau0, m even
+    Theo1,
+    /// bias removed `theo1` deviation
+    TheoBR,
+    /// hybrid `theo` deviation:
+    /// `allan` deviation up to 10% of the record length,
+    /// `theoBR` deviation above
+    TheoH,
+    /// `parabolic` deviation,
+    /// for least squares (Omega) frequency counters
+    Parabolic,
+    /// gap resistant `allan` deviation,
+    /// input may contain f64::NAN to mark missing samples
+    GapResistant,
 }
 
 /// Computes desired deviation over input data 
@@ -46,57 +90,165 @@ pub enum Deviation {
 /// is_fractional: true if input vector is made of fractional (n.a) data
 /// overlapping: true if using overlapping interval (increase confidence / errbar narrows down faster)
 /// returns: (dev, err) : deviation & statistical error bars for each
-/// feasible `tau`
-pub fn deviation (data: &Vec<f64>, taus: &Vec<f64>, calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool) 
+/// feasible `tau`, err = dev / sqrt(2 * edf)
+pub fn deviation (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool) 
         -> Result<(Vec<f64>,Vec<f64>), Error> 
 {
-    tau::tau_sanity_checks(&taus)?;
+    let (devs, edfs, _, _) = deviation_edf(data, taus, calc, sample_rate, is_fractional, overlapping)?;
+    let errs: Vec<f64> = devs.iter()
+        .zip(edfs.iter())
+        .map(|(dev, edf)| dev / (2.0_f64 * edf).powf(0.5_f64))
+        .collect();
+    Ok((devs, errs))
+}
+
+/// Computes desired deviation over input data   
+/// for desired tau values, with chi-squared confidence intervals.   
+/// data: input vector   
+/// taus: desired `tau` offsets (s)   
+/// calc: desired deviation   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// confidence: confidence level, in ]0, 1[, for example 0.683 or 0.95   
+/// returns: (dev, (lower, upper)) : deviation & confidence interval   
+/// bounds for each feasible `tau`, up to the first `tau`   
+/// whose degrees of freedom are not usable
+#[allow(clippy::type_complexity)]
+pub fn deviation_ci (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool,
+        confidence: f64) -> Result<(Vec<f64>,Vec<(f64,f64)>), Error>
+{
+    if !(confidence > 0.0_f64 && confidence < 1.0_f64) {
+        return Err(Error::InvalidConfidenceLevel)
+    }
+    let (mut devs, edfs, _, _) = deviation_edf(data, taus, calc, sample_rate, is_fractional, overlapping)?;
+    let mut bounds: Vec<(f64,f64)> = Vec::with_capacity(devs.len());
+    for (dev, edf) in devs.iter().zip(edfs.iter()) {
+        match confidence::confidence_interval(*dev, *edf, confidence) {
+            Ok(interval) => bounds.push(interval),
+            Err(Error::InvalidDegreesOfFreedom) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    devs.truncate(bounds.len());
+    Ok((devs, bounds))
+}
+
+/// Evaluates desired deviation, the equivalent degrees of freedom   
+/// of each estimate, the noise process (alpha) they rely on   
+/// and the equivalent number of phase samples they were evaluated on
+#[allow(clippy::type_complexity)]
+pub(crate) fn deviation_edf (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool)
+        -> Result<(Vec<f64>,Vec<f64>,Vec<i32>,Vec<usize>), Error>
+{
+    tau::tau_feasibility_checks(taus, calc, sample_rate)?;
+    let raw = data;
     let data = match is_fractional {
-        true => utils::fractional_integral(data, 1.0_f64),
-        false => data.clone(),
+        true => utils::fractional_integral(&data.to_vec(), 1.0_f64),
+        false => data.to_vec(),
+    };
+    // theoBR bias is evaluated once, over the whole record,
+    // when first required
+    let mut theo_bias: Option<Option<f64>> = None;
+
+    // noise identification skips missing samples
+    let valid: Vec<f64>;
+    let (id_data, id_fractional): (&[f64], bool) = match calc {
+        Deviation::GapResistant => {
+            valid = raw.iter()
+                .filter(|x| !x.is_nan())
+                .copied()
+                .collect();
+            (&valid, is_fractional)
+        },
+        _ => (&data, false),
     };
 
     let mut devs: Vec<f64> = Vec::new();
-    let mut errs: Vec<f64> = Vec::new();
+    let mut edfs: Vec<f64> = Vec::new();
+    let mut alphas: Vec<i32> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    // last identified noise process,
+    // assumes white FM until proven otherwise
+    let mut alpha: i32 = 0;
 
     for i in 0..taus.len() {
         let tau = taus[i] * sample_rate;
-        match calc {
-            Deviation::Allan => {
-                if let Ok((dev, err)) = calc_adev(&data, tau, sample_rate, overlapping) {
-                    devs.push(dev);
-                    errs.push(err)
-                } else {
-                    break
-                }
+        let dmax = match calc {
+            Deviation::Hadamard | Deviation::HadamardTotal | Deviation::ModifiedHadamard => 3,
+            _ => 2,
+        };
+        if let Some(a) = utils::lag1_alpha(id_data, tau as usize, dmax, id_fractional) {
+            if a.is_finite() {
+                alpha = a.round() as i32
+            }
+        }
+        // equivalent number of phase samples, for EDF evaluation
+        let mut size = data.len();
+        let result = match calc {
+            Deviation::Allan => calc_adev(&data, tau, sample_rate, overlapping),
+            Deviation::Modified => calc_mdev(&data, tau, sample_rate),
+            Deviation::Time => calc_tdev(&data, tau, sample_rate),
+            Deviation::Hadamard => calc_hdev(&data, tau, sample_rate, overlapping),
+            Deviation::Total => {
+                calc_totdev(&data, tau, sample_rate)
+                    .map(|(dev, err)| {
+                        let bias = totvar_bias(alpha, tau as usize, data.len()).powf(0.5_f64);
+                        (dev / bias, err / bias)
+                    })
             },
-            Deviation::Modified => {
-                if let Ok((dev, err)) = calc_mdev(&data, tau, sample_rate) {
-                    devs.push(dev);
-                    errs.push(err)
-                } else {
-                    break
-                }
+            Deviation::ModifiedTotal => {
+                calc_mtotdev(&data, tau, sample_rate)
+                    .map(|(dev, err)| {
+                        let bias = mtotvar_bias(alpha, tau as usize).powf(0.5_f64);
+                        (dev * bias, err * bias)
+                    })
             },
-            Deviation::Time => {
-                if let Ok((dev, err)) = calc_tdev(&data, tau, sample_rate) {
-                    devs.push(dev);
-                    errs.push(err)
-                } else {
-                    break
-                }
+            Deviation::TimeTotal => {
+                calc_ttotdev(&data, tau, sample_rate)
+                    .map(|(dev, err)| {
+                        let bias = mtotvar_bias(alpha, tau as usize).powf(0.5_f64);
+                        (dev * bias, err * bias)
+                    })
             },
-            Deviation::Hadamard => {
-                if let Ok((dev, err)) = calc_hdev(&data, tau, sample_rate, overlapping) {
-                    devs.push(dev);
-                    errs.push(err)
-                } else {
-                    break
-                }
+            Deviation::HadamardTotal => calc_htotdev(&data, tau, sample_rate, overlapping),
+            Deviation::ModifiedHadamard => calc_mhdev(&data, tau, sample_rate),
+            Deviation::Theo1 => calc_theo1(&data, tau, sample_rate),
+            Deviation::TheoBR => {
+                let bias = *theo_bias.get_or_insert_with(|| theobr_bias(&data).ok());
+                calc_theobr(&data, tau, sample_rate, bias)
+            },
+            Deviation::TheoH => {
+                let bias = match theoh_is_theobr(data.len(), tau) {
+                    true => *theo_bias.get_or_insert_with(|| theobr_bias(&data).ok()),
+                    false => None,
+                };
+                calc_theoh(&data, tau, sample_rate, bias)
             },
+            Deviation::Parabolic => calc_pdev(&data, tau, sample_rate),
+            Deviation::GapResistant => {
+                calc_gradev(raw, tau, sample_rate, is_fractional, overlapping)
+                    .map(|(dev, err, n)| {
+                        // only valid terms contribute
+                        let stride = match overlapping {
+                            true => 1,
+                            false => tau as usize,
+                        };
+                        size = n * stride + 2 * tau as usize;
+                        (dev, err)
+                    })
+            },
+        };
+        if let Ok((dev, _)) = result {
+            devs.push(dev);
+            edfs.push(confidence::edf(calc, alpha, tau, size, overlapping));
+            alphas.push(alpha);
+            sizes.push(size)
+        } else {
+            break
         }
     }
-    Ok((devs, errs))
+    Ok((devs, edfs, alphas, sizes))
 }
 
 /// Computes desired variance over input data 
@@ -117,12 +269,162 @@ pub fn variance (data: &Vec<f64>, taus: &Vec<f64>, dev: Deviation, sample_rate:
     }
     Ok((var, err))
 }
+
+/// Computes desired variance over input data   
+/// for desired tau values, with chi-squared confidence intervals.   
+/// data: input vector   
+/// taus: desired `tau` offsets (s)   
+/// calc: desired deviation   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// confidence: confidence level, in ]0, 1[, for example 0.683 or 0.95   
+/// returns: (var, (lower, upper)) : variance & confidence interval   
+/// bounds for each feasible `tau`
+#[allow(clippy::type_complexity)]
+pub fn variance_ci (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool,
+        confidence: f64) -> Result<(Vec<f64>,Vec<(f64,f64)>), Error>
+{
+    let (devs, bounds) = deviation_ci(data, taus, calc, sample_rate, is_fractional, overlapping, confidence)?;
+    Ok((
+        devs.iter().map(|dev| dev.powf(2.0_f64)).collect(),
+        bounds.iter().map(|(lo, hi)| (lo.powf(2.0_f64), hi.powf(2.0_f64))).collect(),
+    ))
+}
+
+/// Computes desired deviation over input data   
+/// for desired tau values, with block bootstrap confidence intervals.   
+/// Useful when the EDF of the statistics is not well established.   
+/// data: input vector   
+/// taus: desired `tau` offsets (s)   
+/// calc: desired deviation   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// opts: bootstrap options   
+/// rng: random generator, seed it for reproducible intervals   
+/// returns: (dev, (lower, upper)) : deviation & percentile confidence   
+/// interval bounds for each feasible `tau`
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn deviation_bootstrap<R: Rng> (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool,
+        opts: &confidence::Bootstrap, rng: &mut R) -> Result<(Vec<f64>,Vec<(f64,f64)>), Error>
+{
+    let (devs, _) = deviation(data, taus, calc, sample_rate, is_fractional, overlapping)?;
+    let bounds = confidence::bootstrap(&[data], is_fractional, opts, rng, |series| {
+        deviation(&series[0], taus, calc, sample_rate, is_fractional, overlapping)
+            .map(|(devs, _)| devs)
+    })?;
+    Ok((devs, bounds))
+}
+
+/// Computes dynamic deviation over input data:   
+/// desired deviation is evaluated over a window sliding over the data.   
+/// data: input vector   
+/// taus: desired `tau` offsets (s)   
+/// calc: desired deviation   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// window: sliding window length (samples)   
+/// step: sliding window step (samples)   
+/// returns: (t, devs) : t is the center of each window (s),   
+/// devs[i][j] is the deviation at taus[i] over window j,   
+/// f64::NAN when taus[i] is not feasible over the window
+#[allow(clippy::too_many_arguments)]
+pub fn dynamic_deviation (data: &[f64], taus: &[f64], calc: Deviation, sample_rate: f64, is_fractional: bool, overlapping: bool,
+        window: usize, step: usize) -> Result<(Vec<f64>,Vec<Vec<f64>>), Error>
+{
+    if window == 0 || step == 0 {
+        return Err(Error::InvalidWindowError)
+    }
+    if window > data.len() {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let mut t: Vec<f64> = Vec::new();
+    let mut devs: Vec<Vec<f64>> = vec![Vec::new(); taus.len()];
+    let mut start: usize = 0;
+    while start + window <= data.len() {
+        let (dev, _) = deviation(&data[start..start+window], taus, calc, sample_rate, is_fractional, overlapping)?;
+        for (i, row) in devs.iter_mut().enumerate() {
+            row.push(*dev.get(i).unwrap_or(&f64::NAN))
+        }
+        t.push((start as f64 + window as f64 / 2.0_f64) / sample_rate);
+        start += step
+    }
+    Ok((t, devs))
+}
+
+/// Computes Allan covariance between two synchronized series   
+/// for desired tau values.   
+/// data_a: first input vector   
+/// data_b: second input vector, sampled at the same instants   
+/// taus: desired `tau` offsets (s)   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vectors are made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// returns: (gcov, rho) : Allan covariance & correlation coefficient   
+/// for each feasible `tau`
+pub fn covariance (data_a: &[f64], data_b: &[f64], taus: &[f64], sample_rate: f64, is_fractional: bool, overlapping: bool)
+        -> Result<(Vec<f64>,Vec<f64>), Error>
+{
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    if data_a.len() != data_b.len() {
+        return Err(Error::SeriesLengthMismatch)
+    }
+    let (data_a, data_b) = match is_fractional {
+        true => (utils::fractional_integral(&data_a.to_vec(), 1.0_f64),
+                    utils::fractional_integral(&data_b.to_vec(), 1.0_f64)),
+        false => (data_a.to_vec(), data_b.to_vec()),
+    };
+
+    let mut gcovs: Vec<f64> = Vec::new();
+    let mut rhos: Vec<f64> = Vec::new();
+    for tau in taus {
+        if let Ok((gcov, rho)) = calc_gcov(&data_a, &data_b, tau * sample_rate, sample_rate, overlapping) {
+            gcovs.push(gcov);
+            rhos.push(rho)
+        } else {
+            break
+        }
+    }
+    Ok((gcovs, rhos))
+}
+
+/// Computes gap resistant Allan deviation over input data   
+/// for desired tau values.   
+/// data: input vector, f64::NAN marks missing samples   
+/// taus: desired `tau` offsets (s)   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input vector is made of fractional (n.a) data   
+/// overlapping: true if using overlapping interval   
+/// returns: (dev, err, n) : deviation, statistical error bars   
+/// and effective number of terms, for each feasible `tau`
+#[allow(clippy::type_complexity)]
+pub fn gradev (data: &[f64], taus: &[f64], sample_rate: f64, is_fractional: bool, overlapping: bool)
+        -> Result<(Vec<f64>,Vec<f64>,Vec<usize>), Error>
+{
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    let mut devs: Vec<f64> = Vec::new();
+    let mut errs: Vec<f64> = Vec::new();
+    let mut ns: Vec<usize> = Vec::new();
+    for tau in taus {
+        if let Ok((dev, err, n)) = calc_gradev(data, tau * sample_rate, sample_rate, is_fractional, overlapping) {
+            devs.push(dev);
+            errs.push(err);
+            ns.push(n)
+        } else {
+            break
+        }
+    }
+    Ok((devs, errs, ns))
+}
 /// Computes Allan deviation
 /// @ given tau on input data.   
 /// tau: offset (s)    
 /// sample_rate: (Hz)   
 /// overlapping: true for overlapped deviation
-fn calc_adev (data: &Vec<f64>, tau: f64, sample_rate: f64, overlapping: bool) -> Result<(f64,f64), Error> {
+fn calc_adev (data: &[f64], tau: f64, sample_rate: f64, overlapping: bool) -> Result<(f64,f64), Error> {
     let tau_u: usize = tau as usize;
     let stride: usize = match overlapping {
         true => 1,
@@ -186,7 +488,7 @@ fn calc_mdev (data: &Vec<f64>, tau: f64, sample_rate: f64) -> Result<(f64,f64),
 fn calc_tdev (data: &Vec<f64>, tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
     let (mdev, mderr) = calc_mdev(data, tau, sample_rate)?;
     Ok((
-        mdev * tau / (3.0_f64).powf(0.5_f64),
+        mdev * tau / sample_rate / (3.0_f64).powf(0.5_f64),
         mderr // mderr / ns.powf(0.5_f64)
     ))
 }
@@ -217,6 +519,421 @@ fn calc_hdev (data: &Vec<f64>, tau: f64, sample_rate: f64, overlapping: bool) ->
     Ok((dev, dev/(n.powf(0.5_f64))))
 }
 
+/// Computes `total` deviation   
+/// @ given tau on input data.   
+/// Phase data is extended by symmetric reflection   
+/// at both ends, which improves confidence at long tau.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// Totdev is always computed in overlapping fashion
+fn calc_totdev (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    let size = data.len();
+    if size < 3 || tau_u > (size-1) / 2 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    // x*(1-j) = 2x(1) - x(1+j)
+    // x*(N+j) = 2x(N) - x(N-j)
+    let reflected = |i: usize| -> f64 {
+        if i < tau_u {
+            2.0_f64 * data[0] - data[tau_u - i]
+        } else if i - tau_u >= size {
+            2.0_f64 * data[size-1] - data[2*(size-1) + tau_u - i]
+        } else {
+            data[i - tau_u]
+        }
+    };
+
+    let mut sum = 0.0_f64;
+    for (i, x) in data.iter().enumerate().take(size-1).skip(1) {
+        // indexes are shifted by tau_u, to remain positive
+        sum += (reflected(i) - 2.0_f64*x + reflected(i+2*tau_u)).powf(2.0_f64)
+    }
+    let n = (size - 2) as f64;
+    let dev = (sum / 2.0_f64 / n).powf(0.5_f64) / tau * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Computes `modified total` deviation   
+/// @ given tau on input data.   
+/// Each 3*tau subsequence has its linear frequency offset removed,   
+/// and is extended to 9*tau by uninverted even reflection,   
+/// prior modified Allan variance evaluation.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// Mtotdev is always computed in overlapping fashion.   
+/// At tau0, the half averages trend removal leaves x*(0) = x*(2)
+/// in each 3 samples subsequence, so the estimator reduces to
+/// exactly Mvar / 2 whatever the noise: mdev is returned instead
+fn calc_mtotdev (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    let size = data.len();
+    if tau_u == 0 || 3*tau_u > size {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    if tau_u == 1 {
+        return calc_mdev(&data.to_vec(), tau, sample_rate)
+    }
+    let (sum, n) = total_sum(data, tau_u, 2)?;
+    let dev = (sum / 2.0_f64 / n).powf(0.5_f64) / tau * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Returns sum of `modified total` (order 2) or `hadamard total` (order 3)
+/// variance terms, not normalized, and number of 3*tau subsequences.   
+/// Order 2 works on phase data, order 3 on its first difference (frequency).
+/// Each 3*tau subsequence has its linear trend removed, from first & last halves
+/// averages, and is extended to 9*tau by uninverted even reflection,
+/// prior 6*tau overlapping second differences of tau averages evaluation
+fn total_sum (data: &[f64], tau_u: usize, order: usize) -> Result<(f64, f64), Error> {
+    let freq: Vec<f64>;
+    let series: &[f64] = match order {
+        3 => {
+            freq = utils::diff(&data.to_vec(), None);
+            &freq
+        },
+        _ => data,
+    };
+    let size = series.len();
+    if tau_u == 0 || 3*tau_u > size {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let mut sum = 0.0_f64;
+    let mut xstar: Vec<f64> = vec![0.0_f64; 9*tau_u];
+    for n in 0..size-3*tau_u+1 {
+        let xs = &series[n..n+3*tau_u];
+        // linear trend, from first & last halves
+        let (half1, half2) = (3*tau_u / 2, (3*tau_u).div_ceil(2));
+        let mean1 = statistical::mean(&xs[..half1]);
+        let mean2 = statistical::mean(&xs[half2..]);
+        let slope = (mean2 - mean1) / (3*tau_u - half1) as f64;
+        // trend removal & uninverted even reflection
+        for (i, x) in xs.iter().enumerate() {
+            let x0 = x - slope * i as f64;
+            xstar[3*tau_u -1 -i] = x0;
+            xstar[3*tau_u +i] = x0;
+            xstar[9*tau_u -1 -i] = x0
+        }
+        // 6*tau overlapping terms
+        let mut s1: f64 = xstar[..tau_u].iter().sum();
+        let mut s2: f64 = xstar[tau_u..2*tau_u].iter().sum();
+        let mut s3: f64 = xstar[2*tau_u..3*tau_u].iter().sum();
+        let mut squares = 0.0_f64;
+        for j in 0..6*tau_u {
+            squares += (s1 - 2.0_f64*s2 + s3).powf(2.0_f64);
+            if j < 6*tau_u -1 {
+                s1 += xstar[j+tau_u] - xstar[j];
+                s2 += xstar[j+2*tau_u] - xstar[j+tau_u];
+                s3 += xstar[j+3*tau_u] - xstar[j+2*tau_u]
+            }
+        }
+        sum += squares / (tau_u * tau_u) as f64 / (6*tau_u) as f64
+    }
+    Ok((sum, (size - 3*tau_u +1) as f64))
+}
+
+/// Computes `time total` deviation at desired `tau` offset (s).   
+/// sample_rate: sampling rate (Hz)
+fn calc_ttotdev (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let (mtotdev, mtoterr) = calc_mtotdev(data, tau, sample_rate)?;
+    Ok((
+        mtotdev * tau / sample_rate / (3.0_f64).powf(0.5_f64),
+        mtoterr * tau / sample_rate / (3.0_f64).powf(0.5_f64),
+    ))
+}
+
+/// Computes `modified hadamard` deviation   
+/// @ given tau on input data.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// Mhdev is always computed in overlapping fashion
+fn calc_mhdev (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    if tau_u == 0 || 4*tau_u > data.len() {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let third_diff = |i: usize| -> f64 {
+        data[i+3*tau_u] - 3.0_f64*data[i+2*tau_u] + 3.0_f64*data[i+tau_u] - data[i]
+    };
+
+    let mut v: f64 = (0..tau_u)
+        .map(third_diff)
+        .sum();
+    let mut sum = v.powf(2.0_f64);
+    let mut n = 1.0_f64;
+    for i in 0..data.len() -4*tau_u {
+        v += third_diff(i+tau_u) - third_diff(i);
+        sum += v.powf(2.0_f64);
+        n += 1.0_f64
+    }
+    let dev = (sum /6.0_f64 /tau /tau / n).powf(0.5_f64) / tau * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Computes `hadamard total` deviation   
+/// @ given tau on input data.   
+/// Each 3*tau subsequence of frequency data has its linear drift removed,   
+/// and is extended to 9*tau by uninverted even reflection,   
+/// prior Hadamard variance evaluation.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// overlapping: only applies to tau = tau0, evaluated as hdev
+fn calc_htotdev (data: &[f64], tau: f64, sample_rate: f64, overlapping: bool) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    if tau_u == 0 || data.len() < 2 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    if tau_u == 1 {
+        return calc_hdev(&data.to_vec(), tau, sample_rate, overlapping)
+    }
+    let (sum, n) = total_sum(data, tau_u, 3)?;
+    let dev = (sum / 6.0_f64 / n).powf(0.5_f64) * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Computes `theo1` deviation   
+/// @ given tau on input data.   
+/// tau: offset (s), must be 0.75 * m * tau0, m even   
+/// sample_rate: (Hz)   
+/// Theo1 is always computed in overlapping fashion
+fn calc_theo1 (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let m = (tau / 0.75_f64).round() as usize;
+    let size = data.len();
+    if m < 2 || !m.is_multiple_of(2) || m >= size {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let half = m / 2;
+    let mut sum = 0.0_f64;
+    for i in 0..size-m {
+        for d in 0..half {
+            let v = data[i] - data[i+half-d] + data[i+m] - data[i+half+d];
+            sum += v.powf(2.0_f64) / (half - d) as f64
+        }
+    }
+    let n = (size - m) as f64;
+    let dev = (sum / 0.75_f64 / n).powf(0.5_f64) / m as f64 * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Evaluates `theoBR` bias, Avar/Theo1 averaged
+/// over the record (NIST SP1065 §5.2.14)
+fn theobr_bias (data: &[f64]) -> Result<f64, Error> {
+    let n = (0.1_f64 * data.len() as f64 / 3.0_f64 - 3.0_f64).floor();
+    if n < 0.0_f64 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let n = n as usize;
+    let mut sum = 0.0_f64;
+    for i in 0..n+1 {
+        let (adev, _) = calc_adev(data, (9 + 3*i) as f64, 1.0_f64, true)?;
+        let (theo1, _) = calc_theo1(data, (9 + 3*i) as f64, 1.0_f64)?;
+        sum += (adev / theo1).powf(2.0_f64)
+    }
+    Ok(sum / (n + 1) as f64)
+}
+
+/// Computes bias removed `theo1` deviation   
+/// @ given tau on input data.   
+/// tau: offset (s), must be 0.75 * m * tau0, m even   
+/// sample_rate: (Hz)   
+/// bias: `theoBR` bias, None if record is too short
+fn calc_theobr (data: &[f64], tau: f64, sample_rate: f64, bias: Option<f64>) -> Result<(f64,f64), Error> {
+    let bias = bias.ok_or(Error::NotEnoughSamplesError)?
+        .powf(0.5_f64);
+    let (dev, err) = calc_theo1(data, tau, sample_rate)?;
+    Ok((dev * bias, err * bias))
+}
+
+/// Computes hybrid `theo` deviation   
+/// @ given tau on input data.   
+/// Overlapping Allan deviation is used up to 10% of the record length,   
+/// or for taus that are not 0.75 * even multiple of tau0,   
+/// `theoBR` deviation is used above.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// bias: `theoBR` bias, None if record is too short
+fn calc_theoh (data: &[f64], tau: f64, sample_rate: f64, bias: Option<f64>) -> Result<(f64,f64), Error> {
+    match theoh_is_theobr(data.len(), tau) {
+        true => calc_theobr(data, tau, sample_rate, bias),
+        false => calc_adev(data, tau, sample_rate, true),
+    }
+}
+
+/// Returns true if hybrid `theo` deviation is evaluated
+/// as `theoBR` for this `tau` (samples), over a record of `size` samples
+fn theoh_is_theobr (size: usize, tau: f64) -> bool {
+    tau::is_theo_multiple(tau) && tau > 0.1_f64 * (size - 1) as f64
+}
+
+/// Computes `parabolic` deviation   
+/// @ given tau on input data.   
+/// Each term is a least squares frequency estimate difference,   
+/// evaluated with a running sum to remain O(N).   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// Pdev is always computed in overlapping fashion,   
+/// and matches adev @ tau0
+fn calc_pdev (data: &[f64], tau: f64, sample_rate: f64) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    let size = data.len();
+    if tau_u == 0 || 2*tau_u >= size {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    if tau_u == 1 {
+        return calc_adev(data, tau, sample_rate, true)
+    }
+    // c(i) = sum_k w(k) * d(i+k), w(k) = (m-1)/2 -k, d(j) = x(j) - x(j+m)
+    let d: Vec<f64> = (0..size-tau_u)
+        .map(|j| data[j] - data[j+tau_u])
+        .collect();
+    let w = |k: usize| -> f64 { (tau_u as f64 - 1.0_f64) / 2.0_f64 - k as f64 };
+    let mut c: f64 = (0..tau_u)
+        .map(|k| w(k) * d[k])
+        .sum();
+    let mut s: f64 = d[..tau_u].iter().sum();
+
+    let n = size - 2*tau_u +1;
+    let mut sum = c.powf(2.0_f64);
+    for i in 0..n-1 {
+        s += d[i+tau_u] - d[i];
+        c += w(tau_u) * d[i+tau_u] - w(0) * d[i] + s;
+        sum += c.powf(2.0_f64)
+    }
+    let n = n as f64;
+    let dev = (72.0_f64 * sum / n).powf(0.5_f64) / tau / tau / tau * sample_rate;
+    Ok((dev, dev/(n.powf(0.5_f64))))
+}
+
+/// Computes gap resistant Allan deviation   
+/// @ given tau on input data, second differences   
+/// involving a missing (f64::NAN) sample are skipped.   
+/// data: raw input data   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// is_fractional: true for fractional frequency data,   
+/// a missing sample then invalidates all second differences spanning it   
+/// overlapping: true for overlapped deviation   
+/// returns: (dev, err, n) where n is the number of valid terms
+fn calc_gradev (data: &[f64], tau: f64, sample_rate: f64, is_fractional: bool, overlapping: bool) -> Result<(f64,f64,usize), Error> {
+    let tau_u: usize = tau as usize;
+    let stride: usize = match overlapping {
+        true => 1,
+        false => tau_u,
+    };
+    if tau_u == 0 || data.is_empty() || tau_u > (data.len()-1) / 2 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+
+    // phase data & cumulated number of missing
+    // fractional samples, for fractional input
+    let (phase, gaps): (Vec<f64>, Option<Vec<usize>>) = match is_fractional {
+        true => {
+            let mut phase: Vec<f64> = Vec::with_capacity(data.len());
+            let mut gaps: Vec<usize> = Vec::with_capacity(data.len()+1);
+            let (mut x, mut missing) = (0.0_f64, 0_usize);
+            gaps.push(missing);
+            for y in data {
+                if y.is_nan() {
+                    missing += 1
+                } else {
+                    x += y
+                }
+                phase.push(x);
+                gaps.push(missing)
+            }
+            (phase, Some(gaps))
+        },
+        false => (data.to_vec(), None),
+    };
+
+    let mut i: usize = 0;
+    let mut n: usize = 0;
+    let mut sum = 0.0_f64;
+    while i < phase.len() -2*tau_u {
+        let valid = match &gaps {
+            // samples ]i, i+2tau] are involved
+            Some(gaps) => gaps[i+2*tau_u+1] == gaps[i+1],
+            None => true,
+        };
+        let v = phase[i+2*tau_u] - 2.0_f64*phase[i+tau_u] + phase[i];
+        if valid && !v.is_nan() {
+            sum += v.powf(2.0_f64);
+            n += 1
+        }
+        i += stride
+    }
+    if n == 0 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+
+    let dev = (sum / 2.0_f64 / n as f64).powf(0.5_f64) / tau * sample_rate;
+    Ok((dev, dev/((n as f64).powf(0.5_f64)), n))
+}
+
+/// Computes Allan covariance   
+/// @ given tau on input phase data.   
+/// tau: offset (s)   
+/// sample_rate: (Hz)   
+/// overlapping: true for overlapped covariance   
+/// returns: (gcov, rho) covariance and correlation coefficient
+fn calc_gcov (data_a: &[f64], data_b: &[f64], tau: f64, sample_rate: f64, overlapping: bool) -> Result<(f64,f64), Error> {
+    let tau_u: usize = tau as usize;
+    let stride: usize = match overlapping {
+        true => 1,
+        false => tau_u,
+    };
+    if tau_u == 0 || data_a.is_empty() || tau_u > (data_a.len()-1) / 2 {
+        return Err(Error::NotEnoughSamplesError)
+    }
+
+    let mut i: usize = 0;
+    let mut n = 0.0_f64;
+    let (mut sum_ab, mut sum_aa, mut sum_bb) = (0.0_f64, 0.0_f64, 0.0_f64);
+    while i < data_a.len() -2*tau_u {
+        let va = data_a[i+2*tau_u] - 2.0_f64*data_a[i+tau_u] + data_a[i];
+        let vb = data_b[i+2*tau_u] - 2.0_f64*data_b[i+tau_u] + data_b[i];
+        sum_ab += va * vb;
+        sum_aa += va.powf(2.0_f64);
+        sum_bb += vb.powf(2.0_f64);
+        n += 1.0_f64;
+        i += stride
+    }
+    let gcov = sum_ab / 2.0_f64 / n / tau / tau * sample_rate * sample_rate;
+    let rho = sum_ab / (sum_aa * sum_bb).powf(0.5_f64);
+    Ok((gcov, rho))
+}
+
+/// Returns `total` variance bias, Totvar/Avar = 1 - a * tau/T,   
+/// for given noise process (NIST SP1065 §5.2.11).   
+/// Phase noises and white FM are unbiased.   
+/// alpha: fractional frequency power law exponent   
+/// tau_u: averaging factor   
+/// size: number of phase samples
+fn totvar_bias (alpha: i32, tau_u: usize, size: usize) -> f64 {
+    let a = match alpha {
+        -1 => 1.0_f64 / 3.0_f64 / 2.0_f64.ln(),
+        a if a < -1 => 0.75_f64,
+        _ => 0.0_f64,
+    };
+    1.0_f64 - a * tau_u as f64 / (size - 1) as f64
+}
+
+/// Returns `modified total` variance bias, Mvar/Mtotvar,   
+/// for given noise process (NIST SP1065 §5.2.12).   
+/// tau_u = 1 is evaluated as mdev and is unbiased
+fn mtotvar_bias (alpha: i32, tau_u: usize) -> f64 {
+    if tau_u < 2 {
+        return 1.0_f64
+    }
+    match alpha {
+        a if a > 1 => 1.06_f64,
+        1 => 1.17_f64,
+        0 => 1.27_f64,
+        -1 => 1.30_f64,
+        _ => 1.31_f64,
+    }
+}
+
 /// Computes desired statistics in `Three Cornerned Hat` fashion.   
 /// data_ab: A against B data   
 /// data_bc: B against C data   
@@ -247,6 +964,59 @@ pub fn three_cornered_hat(data_ab: &Vec<f64>, data_bc: &Vec<f64>, data_ca: &Vec<
     Ok(((a, err_ab),(b, err_bc),(c, err_ca)))
 }
 
+/// Computes Maximum Time Interval Error (MTIE)   
+/// over input phase data, for desired observation intervals.   
+/// Peak to peak values are tracked with monotonic queues,   
+/// which makes the cost independent of the observation interval.   
+/// data: phase / time error data (s)   
+/// taus: desired observation intervals (s)   
+/// sample_rate: sampling rate (Hz)   
+/// returns: mtie for each feasible observation interval
+pub fn mtie (data: &[f64], taus: &[f64], sample_rate: f64) -> Result<Vec<f64>, Error> {
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    let mut mties: Vec<f64> = Vec::with_capacity(taus.len());
+    for tau in taus {
+        let tau_u = (tau * sample_rate) as usize;
+        if tau_u == 0 || tau_u >= data.len() {
+            break
+        }
+        mties.push(calc_mtie(data, tau_u))
+    }
+    Ok(mties)
+}
+
+/// Computes MTIE over all windows of tau_u +1 samples
+fn calc_mtie (data: &[f64], tau_u: usize) -> f64 {
+    let mut maxq: VecDeque<usize> = VecDeque::new();
+    let mut minq: VecDeque<usize> = VecDeque::new();
+    let mut mtie = 0.0_f64;
+    for (i, x) in data.iter().enumerate() {
+        while maxq.back().is_some_and(|&j| data[j] <= *x) {
+            maxq.pop_back();
+        }
+        while minq.back().is_some_and(|&j| data[j] >= *x) {
+            minq.pop_back();
+        }
+        maxq.push_back(i);
+        minq.push_back(i);
+        if i < tau_u {
+            continue
+        }
+        // window is [i-tau_u, i]
+        if maxq.front().is_some_and(|&j| j < i - tau_u) {
+            maxq.pop_front();
+        }
+        if minq.front().is_some_and(|&j| j < i - tau_u) {
+            minq.pop_front();
+        }
+        let tie = data[maxq[0]] - data[minq[0]];
+        if tie > mtie {
+            mtie = tie
+        }
+    }
+    mtie
+}
+
 /// Structure optimized for `real time` / `rolling` computation,   
 /// refer to dedicated documentation
 #[derive(Debug)]
@@ -328,8 +1098,15 @@ impl RealTime {
 
 #[cfg(test)]
 pub mod plotutils;
+#[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+    /// Reproducible white noise, for statistical assertions
+    fn seeded_white_noise (seed: u64, size: usize) -> Vec<f64> {
+        utils::random_with_rng(size, &mut StdRng::seed_from_u64(seed))
+    }
 	use std::str::FromStr;
     #[test]
     fn test_deviation() {
@@ -352,12 +1129,13 @@ mod tests {
             Deviation::Time,
         ];
         // test against pure noise
+        let mut rng = StdRng::seed_from_u64(0);
         for noise in noises {
             let mut input: Vec<f64>;
             if noise.eq("white") {
-                input = noise::white_noise(-10.0,1.0, N)
+                input = noise::white_noise_with_rng(-10.0,1.0, N, &mut rng)
             } else {
-                input = noise::pink_noise(-10.0,1.0, N)
+                input = noise::pink_noise_with_rng(-10.0,1.0, N, &mut rng)
             };
             let is_fract = noise.contains("fm");
 
@@ -384,6 +1162,16 @@ mod tests {
                             Deviation::Modified => fp.push_str("mdev"),
                             Deviation::Hadamard => fp.push_str("hdev"),
                             Deviation::Time => fp.push_str("tdev"),
+                            Deviation::Total => fp.push_str("totdev"),
+                            Deviation::ModifiedTotal => fp.push_str("mtotdev"),
+                            Deviation::TimeTotal => fp.push_str("ttotdev"),
+                            Deviation::HadamardTotal => fp.push_str("htotdev"),
+                            Deviation::ModifiedHadamard => fp.push_str("mhdev"),
+                            Deviation::Theo1 => fp.push_str("theo1"),
+                            Deviation::TheoBR => fp.push_str("theobr"),
+                            Deviation::TheoH => fp.push_str("theoh"),
+                            Deviation::Parabolic => fp.push_str("pdev"),
+                            Deviation::GapResistant => fp.push_str("gradev"),
                         }
                         fp.push_str(".png");
                         plotutils::plot1d_err(
@@ -397,6 +1185,358 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_totdev() {
+        // linear phase is perfectly extended by reflection
+        let data: Vec<f64> = (0..1000)
+            .map(|i| 1.0E-9 * i as f64)
+            .collect();
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 256.0_f64);
+        let (dev, _) = deviation(&data, &taus, Deviation::Total, 1.0_f64, false, false)
+            .unwrap();
+        assert_eq!(dev.len(), taus.len());
+        for d in dev {
+            assert!(d < 1.0E-15)
+        }
+        // white FM: totdev ~ oadev
+        let data = seeded_white_noise(1, 10000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 128.0_f64);
+        let (totdev, _) = deviation(&data, &taus, Deviation::Total, 1.0_f64, true, true)
+            .unwrap();
+        let (oadev, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(totdev.len(), oadev.len());
+        for i in 0..totdev.len() {
+            assert!((totdev[i] / oadev[i] - 1.0_f64).abs() < 0.15_f64)
+        }
+    }
+    #[test]
+    fn test_mtotdev() {
+        // white FM: bias corrected mtotdev ~ mdev
+        let data = seeded_white_noise(2, 10000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 128.0_f64);
+        let (mtotdev, _) = deviation(&data, &taus, Deviation::ModifiedTotal, 1.0_f64, true, true)
+            .unwrap();
+        let (mdev, _) = deviation(&data, &taus, Deviation::Modified, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(mtotdev.len(), mdev.len());
+        for i in 0..mtotdev.len() {
+            assert!((mtotdev[i] / mdev[i] - 1.0_f64).abs() < 0.15_f64)
+        }
+        // ttotdev = tau/sqrt(3) * mtotdev, tau in seconds
+        let (ttotdev, _) = deviation(&data, &taus, Deviation::TimeTotal, 2.0_f64, true, true)
+            .unwrap();
+        let (mtotdev, _) = deviation(&data, &taus, Deviation::ModifiedTotal, 2.0_f64, true, true)
+            .unwrap();
+        for i in 0..ttotdev.len() {
+            let expected = mtotdev[i] * taus[i] / 3.0_f64.powf(0.5_f64);
+            assert!((ttotdev[i] - expected).abs() < 1.0E-9 * expected)
+        }
+        // white FM: ttotdev ~ tdev
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 0.5_f64, 8.0_f64);
+        let (tdev, _) = deviation(&data, &taus, Deviation::Time, 2.0_f64, true, true)
+            .unwrap();
+        let (ttotdev, _) = deviation(&data, &taus, Deviation::TimeTotal, 2.0_f64, true, true)
+            .unwrap();
+        let (mdev, _) = deviation(&data, &taus, Deviation::Modified, 2.0_f64, true, true)
+            .unwrap();
+        for i in 0..tdev.len() {
+            assert!((tdev[i] - mdev[i] * taus[i] / 3.0_f64.powf(0.5_f64)).abs() < 1.0E-9 * tdev[i]);
+            assert!((ttotdev[i] / tdev[i] - 1.0_f64).abs() < 0.15_f64)
+        }
+        // tau0: trend removal leaves a single second difference
+        // per subsequence, whatever the noise
+        let x = seeded_white_noise(12, 1000);
+        let (sum, n) = total_sum(&x, 1, 2).unwrap();
+        let (mdev, _) = calc_mdev(&x, 1.0_f64, 1.0_f64).unwrap();
+        assert!((mdev.powf(2.0_f64) / (sum / 2.0_f64 / n) - 2.0_f64).abs() < 1.0E-9)
+    }
+    #[test]
+    fn test_hadamard_family() {
+        // white FM: htotdev ~ ohdev
+        let data = seeded_white_noise(3, 10000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 128.0_f64);
+        let (htotdev, _) = deviation(&data, &taus, Deviation::HadamardTotal, 1.0_f64, true, true)
+            .unwrap();
+        let (ohdev, _) = deviation(&data, &taus, Deviation::Hadamard, 1.0_f64, true, true)
+            .unwrap();
+        let (mhdev, _) = deviation(&data, &taus, Deviation::ModifiedHadamard, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(htotdev.len(), ohdev.len());
+        assert_eq!(mhdev.len(), ohdev.len());
+        // mhdev = hdev @ tau0
+        assert!((mhdev[0] - ohdev[0]).abs() < 1.0E-9 * ohdev[0]);
+        for i in 0..htotdev.len() {
+            assert!((htotdev[i] / ohdev[i] - 1.0_f64).abs() < 0.15_f64)
+        }
+        // white FM: mhdev ~ tau^-1/2
+        for i in 2..mhdev.len() {
+            assert!((mhdev[i] / mhdev[i-1] - 0.5_f64.powf(0.5_f64)).abs() < 0.1_f64)
+        }
+    }
+    #[test]
+    fn test_theo_family() {
+        // white FM: theo1 ~ oadev, theoBR ~ theo1
+        let data = seeded_white_noise(4, 2000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 12.0_f64, 192.0_f64);
+        let (theo1, _) = deviation(&data, &taus, Deviation::Theo1, 1.0_f64, true, true)
+            .unwrap();
+        let (theobr, _) = deviation(&data, &taus, Deviation::TheoBR, 1.0_f64, true, true)
+            .unwrap();
+        let (oadev, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(theo1.len(), taus.len());
+        assert_eq!(theobr.len(), taus.len());
+        for i in 0..theo1.len() {
+            assert!((theo1[i] / oadev[i] - 1.0_f64).abs() < 0.2_f64);
+            assert!((theobr[i] / theo1[i] - 1.0_f64).abs() < 0.15_f64)
+        }
+        // theo1 reaches 75% of the record length
+        let taus = vec![1350.0_f64];
+        let (theo1, _) = deviation(&data, &taus, Deviation::Theo1, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(theo1.len(), 1);
+        let (oadev, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(oadev.len(), 0);
+        // theoH: oadev at short taus
+        let taus = vec![1.0_f64, 2.0_f64, 3.0_f64, 12.0_f64, 300.0_f64, 1200.0_f64];
+        let (theoh, _) = deviation(&data, &taus, Deviation::TheoH, 1.0_f64, true, true)
+            .unwrap();
+        let (oadev, _) = deviation(&data, &taus[..4], Deviation::Allan, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(theoh.len(), taus.len());
+        for i in 0..oadev.len() {
+            assert_eq!(theoh[i], oadev[i])
+        }
+        // non feasible theo tau
+        let taus = vec![1.0_f64, 2.0_f64];
+        assert!(deviation(&data, &taus, Deviation::Theo1, 1.0_f64, true, true).is_err());
+    }
+    #[test]
+    fn test_pdev() {
+        let data = seeded_white_noise(5, 2000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 512.0_f64);
+        let (pdev, _) = deviation(&data, &taus, Deviation::Parabolic, 1.0_f64, true, true)
+            .unwrap();
+        let (oadev, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true)
+            .unwrap();
+        assert_eq!(pdev.len(), taus.len());
+        // pdev = oadev @ tau0
+        assert_eq!(pdev[0], oadev[0]);
+        // against direct evaluation
+        let phase = utils::fractional_integral(&data, 1.0_f64);
+        for i in 1..taus.len() {
+            let m = taus[i] as usize;
+            let n = phase.len() - 2*m +1;
+            let mut sum = 0.0_f64;
+            for j in 0..n {
+                let mut c = 0.0_f64;
+                for k in 0..m {
+                    c += ((m as f64 - 1.0_f64) / 2.0_f64 - k as f64) * (phase[j+k] - phase[j+k+m])
+                }
+                sum += c.powf(2.0_f64)
+            }
+            let expected = (72.0_f64 * sum / n as f64).powf(0.5_f64) / (m as f64).powf(3.0_f64);
+            assert!((pdev[i] - expected).abs() < 1.0E-6 * expected)
+        }
+    }
+    #[test]
+    fn test_gradev() {
+        let data = seeded_white_noise(6, 10000);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 256.0_f64);
+        for overlapping in [false, true] {
+            // no gaps: gradev = adev
+            let (oadev, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, overlapping)
+                .unwrap();
+            let (grdev, _) = deviation(&data, &taus, Deviation::GapResistant, 1.0_f64, true, overlapping)
+                .unwrap();
+            assert_eq!(grdev.len(), oadev.len());
+            for i in 0..grdev.len() {
+                assert!((grdev[i] - oadev[i]).abs() < 1.0E-9 * oadev[i])
+            }
+            // 10% missing samples, in blocks of 100
+            let is_missing = |i: usize| (i / 100) % 10 == 3;
+            for is_fractional in [false, true] {
+                let input: Vec<f64> = match is_fractional {
+                    true => data.clone(),
+                    false => utils::fractional_integral(&data, 1.0_f64),
+                };
+                let input: Vec<f64> = input.iter()
+                    .enumerate()
+                    .map(|(i, x)| if is_missing(i) { f64::NAN } else { *x })
+                    .collect();
+                let (devs, _, ns) = gradev(&input, &taus, 1.0_f64, is_fractional, overlapping)
+                    .unwrap();
+                assert_eq!(devs.len(), oadev.len());
+                for i in 0..devs.len() {
+                    assert!(!devs[i].is_nan());
+                    assert!(ns[i] > 0);
+                    assert!((devs[i] / oadev[i] - 1.0_f64).abs() < 0.2_f64)
+                }
+                // at least 10% of the terms are lost
+                let full = (data.len() - 2*taus[0] as usize) as f64;
+                assert!((ns[0] as f64) < 0.9 * full);
+                // error bars account for missing samples only
+                let (gdevs, gerrs) = deviation(&input, &taus, Deviation::GapResistant, 1.0_f64, is_fractional, overlapping)
+                    .unwrap();
+                let (_, errs) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, overlapping)
+                    .unwrap();
+                for i in 0..gerrs.len() {
+                    assert!(gerrs[i].is_finite());
+                    assert!(gerrs[i] / gdevs[i] > errs[i] / oadev[i])
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_covariance() {
+        let data_a = seeded_white_noise(7, 10000);
+        let data_b = seeded_white_noise(8, 10000);
+        let opposite: Vec<f64> = data_a.iter()
+            .map(|x| -x)
+            .collect();
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 128.0_f64);
+        for overlapping in [false, true] {
+            // gcov(a, a) = avar(a)
+            let (gcov, rho) = covariance(&data_a, &data_a, &taus, 1.0_f64, true, overlapping)
+                .unwrap();
+            let (avar, _) = variance(&data_a, &taus.to_vec(), Deviation::Allan, 1.0_f64, true, overlapping)
+                .unwrap();
+            assert_eq!(gcov.len(), avar.len());
+            for i in 0..gcov.len() {
+                assert!((gcov[i] - avar[i]).abs() < 1.0E-9 * avar[i]);
+                assert!((rho[i] - 1.0_f64).abs() < 1.0E-9)
+            }
+            let (gcov, rho) = covariance(&data_a, &opposite, &taus, 1.0_f64, true, overlapping)
+                .unwrap();
+            for i in 0..gcov.len() {
+                assert!((gcov[i] + avar[i]).abs() < 1.0E-9 * avar[i]);
+                assert!((rho[i] + 1.0_f64).abs() < 1.0E-9)
+            }
+            // independent series
+            let (_, rho) = covariance(&data_a, &data_b, &taus[..4], 1.0_f64, true, overlapping)
+                .unwrap();
+            for r in rho {
+                assert!(r.abs() < 0.3_f64)
+            }
+        }
+        assert!(covariance(&data_a, &data_b[1..], &taus, 1.0_f64, true, true).is_err());
+    }
+    #[test]
+    fn test_dynamic_deviation() {
+        // noise level increases tenfold halfway through
+        let mut data = seeded_white_noise(9, 4000);
+        for x in data.iter_mut().skip(2000) {
+            *x *= 10.0_f64
+        }
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 1024.0_f64);
+        let (t, devs) = dynamic_deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 1000, 500)
+            .unwrap();
+        assert_eq!(t, vec![500.0_f64, 1000.0, 1500.0, 2000.0, 2500.0, 3000.0, 3500.0]);
+        assert_eq!(devs.len(), taus.len());
+        for row in &devs {
+            assert_eq!(row.len(), t.len())
+        }
+        // 2*512 > 1000
+        for dev in &devs[9] {
+            assert!(dev.is_nan())
+        }
+        for j in 0..3 {
+            assert!(devs[0][j+4] > 5.0_f64 * devs[0][j])
+        }
+        assert!(dynamic_deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 1000, 0).is_err());
+        assert!(dynamic_deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 5000, 10).is_err());
+    }
+    #[test]
+    fn test_confidence_intervals() {
+        let data = seeded_white_noise(10, 4096);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 1024.0_f64);
+        let (devs, _) = deviation(&data, &taus, Deviation::Allan, 1.0_f64, true, true).unwrap();
+        let (devs68, ci68) = deviation_ci(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 0.683).unwrap();
+        let (_, ci95) = deviation_ci(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 0.95).unwrap();
+        assert_eq!(devs, devs68);
+        assert_eq!(ci68.len(), devs.len());
+        for i in 0..devs.len() {
+            let (lo68, hi68) = ci68[i];
+            let (lo95, hi95) = ci95[i];
+            assert!(lo95 < lo68 && lo68 < devs[i] && devs[i] < hi68 && hi68 < hi95);
+            // intervals widen with tau
+            if i > 0 {
+                assert!(hi95 / devs[i] > ci95[i-1].1 / devs[i-1])
+            }
+        }
+        let (vars, var_ci) = variance_ci(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 0.95).unwrap();
+        for i in 0..vars.len() {
+            assert!((vars[i] - devs[i].powf(2.0_f64)).abs() < 1.0E-12 * vars[i]);
+            assert!((var_ci[i].0 - ci95[i].0.powf(2.0_f64)).abs() < 1.0E-12 * vars[i])
+        }
+        assert!(deviation_ci(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 1.0).is_err());
+        assert!(deviation_ci(&data, &taus, Deviation::Allan, 1.0_f64, true, true, 0.0).is_err());
+        // white PM, up to N/2: few terms remain, bounds stay finite
+        let taus = [1024.0_f64, 1536.0, 2047.0];
+        let (devs, ci) = deviation_ci(&data, &taus, Deviation::Allan, 1.0_f64, false, true, 0.95).unwrap();
+        assert_eq!(devs.len(), taus.len());
+        for i in 0..devs.len() {
+            assert!(ci[i].0.is_finite() && ci[i].1.is_finite());
+            assert!(ci[i].0 < devs[i] && devs[i] < ci[i].1)
+        }
+        // random walk FM, over the whole Theo1 range
+        let data: Vec<f64> = seeded_white_noise(11, 1000).iter()
+            .scan(0.0_f64, |acc, x| {
+                *acc += x;
+                Some(*acc)
+            })
+            .collect();
+        let taus: Vec<f64> = (1..500).map(|k| 1.5_f64 * k as f64).collect();
+        let (_, _, alphas, _) = deviation_edf(&data, &taus, Deviation::Theo1, 1.0_f64, true, true).unwrap();
+        assert!(alphas.iter().rev().take(100).all(|a| *a == -2));
+        let (devs, errs) = deviation(&data, &taus, Deviation::Theo1, 1.0_f64, true, true).unwrap();
+        assert_eq!(devs.len(), taus.len());
+        assert!(errs.iter().all(|e| e.is_finite() && *e > 0.0));
+        let (devs, ci) = deviation_ci(&data, &taus, Deviation::Theo1, 1.0_f64, true, true, 0.95).unwrap();
+        assert_eq!(devs.len(), taus.len());
+        assert!(ci.iter().all(|(lo, hi)| lo.is_finite() && hi.is_finite()));
+    }
+    #[test]
+    fn test_bootstrap() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut white = |sigma: f64| -> Vec<f64> {
+            (0..2048)
+                .map(|_| sigma * rng.sample::<f64, _>(StandardNormal))
+                .collect()
+        };
+        let data = white(1.0_f64);
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 256.0_f64);
+        let opts = confidence::Bootstrap {
+            iterations: 200,
+            block_len: Some(16),
+            confidence: 0.95,
+        };
+        let (devs, bounds) = deviation_bootstrap(&data, &taus, Deviation::Allan, 1.0_f64, true, true,
+            &opts, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(bounds.len(), devs.len());
+        for i in 0..devs.len() {
+            assert!(bounds[i].0 < devs[i] && devs[i] < bounds[i].1)
+        }
+        let (_, again) = deviation_bootstrap(&data, &taus, Deviation::Allan, 1.0_f64, true, true,
+            &opts, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(bounds, again);
+        // three cornered hat estimates
+        let (a, b, c) = (white(1.0_f64), white(2.0_f64), white(4.0_f64));
+        let ab: Vec<f64> = a.iter().zip(b.iter()).map(|(a, b)| a - b).collect();
+        let bc: Vec<f64> = b.iter().zip(c.iter()).map(|(b, c)| b - c).collect();
+        let ca: Vec<f64> = c.iter().zip(a.iter()).map(|(c, a)| c - a).collect();
+        let bounds = confidence::bootstrap(&[&ab, &bc, &ca], true, &opts, &mut StdRng::seed_from_u64(0), |s| {
+            three_cornered_hat(&s[0], &s[1], &s[2], &taus, 1.0_f64, true, true, Deviation::Allan)
+                .map(|(_, _, (c, _))| c)
+        }).unwrap();
+        let (_, _, (dev_c, _)) = three_cornered_hat(&ab, &bc, &ca, &taus, 1.0_f64, true, true, Deviation::Allan)
+            .unwrap();
+        for i in 0..dev_c.len() {
+            assert!(bounds[i].0 < dev_c[i] && dev_c[i] < bounds[i].1)
+        }
+    }
     /*
     #[test]
     fn test_against_models() {
@@ -479,9 +1619,10 @@ mod tests {
     }*/
     #[test]
     fn test_three_cornered_hat() {
-        let pm_pink  = utils::diff(&noise::pink_noise(-10.0,1.0,10000),None);
-        let fm_white = noise::white_noise(-10.0,1.0,10000);
-        let fm_pink = noise::pink_noise(-10.0,1.0,10000);
+        let mut rng = StdRng::seed_from_u64(0);
+        let pm_pink  = utils::diff(&noise::pink_noise_with_rng(-10.0,1.0,10000,&mut rng),None);
+        let fm_white = noise::white_noise_with_rng(-10.0,1.0,10000,&mut rng);
+        let fm_pink = noise::pink_noise_with_rng(-10.0,1.0,10000,&mut rng);
         let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 10000.0);
 
         let ((dev_a, err_a),(dev_b,err_b),(dev_c,err_c)) =
@@ -502,6 +1643,62 @@ mod tests {
             (&dev_c,   &err_c),
         );
     }
+    #[test]
+    fn test_three_cornered_hat_correlated() {
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 64.0_f64);
+        let mut rng = StdRng::seed_from_u64(0);
+        let h = [1.0E-20, 1.0E-20, 1.0E-20];
+        // white FM, same level on each clock
+        let expected = (0.5E-20_f64).powf(0.5_f64);
+        for rho in [0.0_f64, 0.8_f64] {
+            let correlation = vec![
+                vec![1.0, rho, 0.0],
+                vec![rho, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0],
+            ];
+            let clocks = noise::correlated_noise_with_rng(0.0, &h, 1.0, 16384, &correlation, &mut rng)
+                .unwrap();
+            let (ab, bc, ca) = utils::three_cornered_differences(&clocks[0], &clocks[1], &clocks[2])
+                .unwrap();
+            let ((a, _), _, (c, _)) = three_cornered_hat(&ab, &bc, &ca, &taus, 1.0_f64, false, true, Deviation::Allan)
+                .unwrap();
+            // correlated noise cancels in A-B:
+            // a^2 = (1 - rho) s^2, c^2 = (1 + rho) s^2
+            let (a_expected, c_expected) = ((1.0 - rho).powf(0.5) * expected, (1.0 + rho).powf(0.5) * expected);
+            assert!((a[0] / a_expected - 1.0).abs() < 0.1);
+            assert!((c[0] / c_expected - 1.0).abs() < 0.1);
+        }
+        let short = vec![0.0_f64; 10];
+        assert!(utils::three_cornered_differences(&short, &short, &short[1..]).is_err());
+    }
+    #[test]
+    fn test_mtie() {
+        // constant frequency offset
+        let data: Vec<f64> = (0..1000)
+            .map(|i| 1.0E-9 * i as f64)
+            .collect();
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 2048.0_f64);
+        let mties = mtie(&data, &taus, 1.0_f64).unwrap();
+        assert_eq!(mties.len(), 10);
+        for i in 0..mties.len() {
+            assert!((mties[i] - 1.0E-9 * taus[i]).abs() < 1.0E-15)
+        }
+        // against naive scan
+        let data = seeded_white_noise(11, 1000);
+        let taus = tau::tau_generator(tau::TauAxis::Decade, 1.0_f64, 100.0_f64);
+        let mties = mtie(&data, &taus, 1.0_f64).unwrap();
+        for i in 0..taus.len() {
+            let n = taus[i] as usize;
+            let mut expected = 0.0_f64;
+            for k in 0..data.len()-n {
+                let window = &data[k..k+n+1];
+                let max = window.iter().cloned().fold(f64::MIN, f64::max);
+                let min = window.iter().cloned().fold(f64::MAX, f64::min);
+                expected = expected.max(max - min)
+            }
+            assert_eq!(mties[i], expected)
+        }
+    }
 /*
     #[test]
     fn test_realtime_core() {
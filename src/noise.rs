@@ -1,18 +1,33 @@
 //! tools / utilities to generate noise distributions
 
-use crate::utils;
+use crate::{utils, Error};
+use rand::Rng;
+use rand_distr::StandardNormal;
 
 /// Generates `white` noise distribution of desired `size`
 /// and desired Power Spectral Density [dBc/Hz]
 pub fn white_noise (psd: f64, sample_rate: f64, size: usize) -> Vec<f64> {
-    let rand = utils::random(size);
+    white_noise_with_rng(psd, sample_rate, size, &mut rand::thread_rng())
+}
+
+/// Generates `white` noise distribution of desired `size`   
+/// and desired Power Spectral Density [dBc/Hz],   
+/// drawn from given random generator
+pub fn white_noise_with_rng<R: Rng> (psd: f64, sample_rate: f64, size: usize, rng: &mut R) -> Vec<f64> {
+    let rand = utils::random_with_rng(size, rng);
     let psd = 10.0_f64.powf(psd/20.0);
     utils::normalize(rand, (2.0_f64/psd/sample_rate).powf(0.5_f64))
 }
 
 pub fn pink_noise (psd: f64, sample_rate: f64, size: usize) -> Vec<f64> {
+    pink_noise_with_rng(psd, sample_rate, size, &mut rand::thread_rng())
+}
+
+/// Generates `pink` noise distribution of desired `size`,   
+/// drawn from given random generator
+pub fn pink_noise_with_rng<R: Rng> (psd: f64, sample_rate: f64, size: usize, rng: &mut R) -> Vec<f64> {
     let mut ret: Vec<f64> = Vec::with_capacity(size);
-    let white = white_noise(psd, sample_rate, size);
+    let white = white_noise_with_rng(psd, sample_rate, size, rng);
     let (mut b0,mut b1,mut b2,mut b3,
         mut b4,mut b5,mut b6) = 
             (0.0_f64,0.0_f64,0.0_f64,
@@ -31,22 +46,500 @@ pub fn pink_noise (psd: f64, sample_rate: f64, size: usize) -> Vec<f64> {
     ret
 }
 
+/// Generates discrete power law noise (Kasdin & Walter 1992),   
+/// for any fractional frequency PSD S_y(f) = h_alpha * f^alpha.   
+/// White noise is filtered by the fractional differencing filter   
+/// h_k = h_(k-1) * (k - 1 - b/2) / k, with b = alpha - 2,   
+/// convolution is performed in the frequency domain.   
+/// alpha: power law exponent in [-4, 2],   
+/// 2: white PM, 1: flicker PM, 0: white FM, -1: flicker FM, -2: random walk FM   
+/// h_alpha: power law coefficient   
+/// tau0: sampling period (s)   
+/// size: number of samples   
+/// returns: phase data (s), use `utils::derivative` to obtain   
+/// fractional frequency data
+pub fn powerlaw_noise (alpha: f64, h_alpha: f64, tau0: f64, size: usize) -> Vec<f64> {
+    powerlaw_noise_with_rng(alpha, h_alpha, tau0, size, &mut rand::thread_rng())
+}
+
+/// Generates discrete power law noise, refer to `powerlaw_noise`,   
+/// white noise source is drawn from given random generator
+pub fn powerlaw_noise_with_rng<R: Rng> (alpha: f64, h_alpha: f64, tau0: f64, size: usize, rng: &mut R) -> Vec<f64> {
+    if size == 0 {
+        return Vec::new()
+    }
+    let b = alpha - 2.0_f64;
+    // discrete variance of the white noise source
+    let qd = h_alpha / 2.0_f64 / (2.0_f64 * std::f64::consts::PI).powf(alpha) / tau0.powf(alpha - 1.0_f64);
+    let n = (2 * size).next_power_of_two();
+    let mut h: Vec<(f64, f64)> = vec![(0.0_f64, 0.0_f64); n];
+    h[0].0 = 1.0_f64;
+    for k in 1..size {
+        h[k].0 = h[k-1].0 * (k as f64 - 1.0_f64 - b / 2.0_f64) / k as f64
+    }
+    let mut w: Vec<(f64, f64)> = vec![(0.0_f64, 0.0_f64); n];
+    for (w, r) in w.iter_mut().zip(utils::random_with_rng(size, rng)) {
+        w.0 = r * qd.powf(0.5_f64)
+    }
+    fft(&mut h, false);
+    fft(&mut w, false);
+    for (w, h) in w.iter_mut().zip(h.iter()) {
+        *w = (w.0 * h.0 - w.1 * h.1, w.0 * h.1 + w.1 * h.0)
+    }
+    fft(&mut w, true);
+    w.iter()
+        .take(size)
+        .map(|w| w.0)
+        .collect()
+}
+
+/// Composite clock model: sum of power law noises,   
+/// plus deterministic time offset, frequency offset and drifts.   
+/// x(t) = x0 + y0 t + D t^2 / 2 + Dr t^3 / 6 + noise
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClockModel {
+    /// power law coefficients h_alpha, indexed by alpha + 2:   
+    /// [h_-2 (random walk FM), h_-1 (flicker FM), h_0 (white FM),   
+    /// h_1 (flicker PM), h_2 (white PM)]
+    pub h: [f64; 5],
+    /// initial time offset x0 (s)
+    pub time_offset: f64,
+    /// initial fractional frequency offset y0 (n.a)
+    pub frequency_offset: f64,
+    /// linear fractional frequency drift D (1/s)
+    pub drift: f64,
+    /// quadratic fractional frequency drift Dr (1/s^2)
+    pub drift_rate: f64,
+}
+
+impl ClockModel {
+    /// Synthesizes phase data (s)   
+    /// tau0: sampling period (s)   
+    /// size: number of samples
+    pub fn phase (&self, tau0: f64, size: usize) -> Vec<f64> {
+        self.phase_with_rng(tau0, size, &mut rand::thread_rng())
+    }
+
+    /// Synthesizes phase data (s),   
+    /// noise terms are drawn from given random generator
+    pub fn phase_with_rng<R: Rng> (&self, tau0: f64, size: usize, rng: &mut R) -> Vec<f64> {
+        let mut x: Vec<f64> = (0..size)
+            .map(|i| {
+                let t = i as f64 * tau0;
+                self.time_offset + self.frequency_offset * t
+                    + self.drift * t.powf(2.0_f64) / 2.0_f64
+                    + self.drift_rate * t.powf(3.0_f64) / 6.0_f64
+            })
+            .collect();
+        for (i, h) in self.h.iter().enumerate() {
+            if *h > 0.0_f64 {
+                let alpha = i as f64 - 2.0_f64;
+                let noise = powerlaw_noise_with_rng(alpha, *h, tau0, size, rng);
+                for (x, n) in x.iter_mut().zip(noise.iter()) {
+                    *x += n
+                }
+            }
+        }
+        x
+    }
+
+    /// Synthesizes fractional frequency data (n.a)   
+    /// tau0: sampling period (s)   
+    /// size: number of samples
+    pub fn fractional_frequency (&self, tau0: f64, size: usize) -> Vec<f64> {
+        self.fractional_frequency_with_rng(tau0, size, &mut rand::thread_rng())
+    }
+
+    /// Synthesizes fractional frequency data (n.a),   
+    /// noise terms are drawn from given random generator
+    pub fn fractional_frequency_with_rng<R: Rng> (&self, tau0: f64, size: usize, rng: &mut R) -> Vec<f64> {
+        let x = self.phase_with_rng(tau0, size + 1, rng);
+        utils::derivative(&x, 1.0_f64 / tau0)
+    }
+}
+
+/// Synthesizes phase data from a single sideband phase noise profile   
+/// L(f), by spectral shaping of white gaussian noise.   
+/// L(f) is interpolated log-log between given points, and held flat   
+/// beyond the first and last points.   
+/// S_phi(f) = 2 * 10^(L(f)/10) (rad^2/Hz), S_x(f) = S_phi(f) / (2 pi nu0)^2 (s^2/Hz).   
+/// profile: (offset frequency (Hz), L(f) (dBc/Hz)) points   
+/// nu0: carrier frequency (Hz)   
+/// sample_rate: sampling rate (Hz)   
+/// size: number of samples   
+/// returns: phase data (s)
+pub fn phase_noise_profile (profile: &[(f64, f64)], nu0: f64, sample_rate: f64, size: usize) -> Result<Vec<f64>, Error> {
+    phase_noise_profile_with_rng(profile, nu0, sample_rate, size, &mut rand::thread_rng())
+}
+
+/// Synthesizes phase data from a single sideband phase noise profile,   
+/// refer to `phase_noise_profile`, noise is drawn from given random generator
+pub fn phase_noise_profile_with_rng<R: Rng> (profile: &[(f64, f64)], nu0: f64, sample_rate: f64, size: usize, rng: &mut R)
+        -> Result<Vec<f64>, Error>
+{
+    if profile.is_empty() {
+        return Err(Error::InvalidPhaseNoiseProfile)
+    }
+    for i in 0..profile.len() {
+        if profile[i].0 <= 0.0_f64 || (i > 0 && profile[i].0 <= profile[i-1].0) {
+            return Err(Error::InvalidPhaseNoiseProfile)
+        }
+    }
+    if size == 0 {
+        return Ok(Vec::new())
+    }
+    let n = size.next_power_of_two();
+    let mut spectrum: Vec<(f64, f64)> = vec![(0.0_f64, 0.0_f64); n];
+    for k in 1..n.div_ceil(2) {
+        let f = k as f64 * sample_rate / n as f64;
+        let s_phi = 2.0_f64 * 10.0_f64.powf(profile_level(profile, f) / 10.0_f64);
+        let s_x = s_phi / (2.0_f64 * std::f64::consts::PI * nu0).powf(2.0_f64);
+        // E|X_k|^2 = S_x(f_k) * fs * N / 2
+        let a = (s_x * sample_rate * n as f64 / 4.0_f64).powf(0.5_f64);
+        let g1: f64 = rng.sample(StandardNormal);
+        let g2: f64 = rng.sample(StandardNormal);
+        spectrum[k] = (a * g1, a * g2);
+        spectrum[n - k] = (a * g1, -a * g2)
+    }
+    fft(&mut spectrum, true);
+    Ok(spectrum.iter()
+        .take(size)
+        .map(|x| x.0)
+        .collect())
+}
+
+/// Returns L(f) (dBc/Hz) at desired offset frequency,   
+/// interpolated log-log, held flat beyond the profile
+fn profile_level (profile: &[(f64, f64)], f: f64) -> f64 {
+    let (first, last) = (profile[0], profile[profile.len()-1]);
+    if f <= first.0 {
+        return first.1
+    }
+    if f >= last.0 {
+        return last.1
+    }
+    let i = profile.iter()
+        .position(|(fi, _)| *fi > f)
+        .unwrap_or(profile.len() - 1);
+    let (f1, l1) = profile[i-1];
+    let (f2, l2) = profile[i];
+    l1 + (l2 - l1) * (f / f1).log10() / (f2 / f1).log10()
+}
+
+/// Generates correlated channels of discrete power law noise,   
+/// refer to `powerlaw_noise`. Independent power law sources are mixed   
+/// with the Cholesky factor of the correlation matrix.   
+/// alpha: power law exponent in [-4, 2]   
+/// h_alpha: power law coefficient of each channel   
+/// tau0: sampling period (s)   
+/// size: number of samples   
+/// correlation: symmetric, positive definite correlation matrix,   
+/// with unit diagonal   
+/// returns: phase data (s) of each channel
+pub fn correlated_noise (alpha: f64, h_alpha: &[f64], tau0: f64, size: usize, correlation: &[Vec<f64>])
+        -> Result<Vec<Vec<f64>>, Error>
+{
+    correlated_noise_with_rng(alpha, h_alpha, tau0, size, correlation, &mut rand::thread_rng())
+}
+
+/// Generates correlated channels of discrete power law noise,   
+/// refer to `correlated_noise`, sources are drawn from given random generator
+pub fn correlated_noise_with_rng<R: Rng> (alpha: f64, h_alpha: &[f64], tau0: f64, size: usize, correlation: &[Vec<f64>], rng: &mut R)
+        -> Result<Vec<Vec<f64>>, Error>
+{
+    let n = h_alpha.len();
+    if correlation.len() != n || correlation.iter().any(|row| row.len() != n) {
+        return Err(Error::InvalidCorrelationMatrix)
+    }
+    for (i, row) in correlation.iter().enumerate() {
+        if (row[i] - 1.0_f64).abs() > 1.0E-9 {
+            return Err(Error::InvalidCorrelationMatrix)
+        }
+        if (0..i).any(|j| (row[j] - correlation[j][i]).abs() > 1.0E-9) {
+            return Err(Error::InvalidCorrelationMatrix)
+        }
+    }
+    let l = cholesky(correlation)
+        .ok_or(Error::InvalidCorrelationMatrix)?;
+    let sources: Vec<Vec<f64>> = (0..n)
+        .map(|_| powerlaw_noise_with_rng(alpha, 1.0_f64, tau0, size, rng))
+        .collect();
+    Ok((0..n)
+        .map(|i| {
+            (0..size)
+                .map(|k| {
+                    let mixed: f64 = (0..=i)
+                        .map(|j| l[i][j] * sources[j][k])
+                        .sum();
+                    mixed * h_alpha[i].powf(0.5_f64)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Cholesky decomposition of a symmetric matrix,   
+/// returns lower triangular L such as L L^T = m,   
+/// None if m is not positive definite
+fn cholesky (m: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = m.len();
+    let mut l: Vec<Vec<f64>> = vec![vec![0.0_f64; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j)
+                .map(|k| l[i][k] * l[j][k])
+                .sum();
+            if i == j {
+                let d = m[i][i] - sum;
+                if d <= 0.0_f64 {
+                    return None
+                }
+                l[i][j] = d.powf(0.5_f64)
+            } else {
+                l[i][j] = (m[i][j] - sum) / l[j][j]
+            }
+        }
+    }
+    Some(l)
+}
+
+/// In place radix-2 fast Fourier transform,   
+/// data length must be a power of two.   
+/// inverse: true for inverse transform (normalized)
+fn fft (data: &mut [(f64, f64)], inverse: bool) {
+    let n = data.len();
+    // bit reversal permutation
+    let mut j: usize = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j)
+        }
+    }
+    let sign = match inverse {
+        true => 1.0_f64,
+        false => -1.0_f64,
+    };
+    let mut len: usize = 2;
+    while len <= n {
+        let angle = sign * 2.0_f64 * std::f64::consts::PI / len as f64;
+        let (wr, wi) = (angle.cos(), angle.sin());
+        for start in (0..n).step_by(len) {
+            let (mut cr, mut ci) = (1.0_f64, 0.0_f64);
+            for k in 0..len/2 {
+                let (ar, ai) = data[start + k];
+                let (br, bi) = data[start + k + len/2];
+                let (tr, ti) = (br * cr - bi * ci, br * ci + bi * cr);
+                data[start + k] = (ar + tr, ai + ti);
+                data[start + k + len/2] = (ar - tr, ai - ti);
+                (cr, ci) = (cr * wr - ci * wi, cr * wi + ci * wr)
+            }
+        }
+        len <<= 1
+    }
+    if inverse {
+        for x in data.iter_mut() {
+            *x = (x.0 / n as f64, x.1 / n as f64)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils;
     use crate::plotutils;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_white_noise_generator() {
-        let samples = white_noise(-10.0, 1.0, 1000);
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples = white_noise_with_rng(-10.0, 1.0, 1000, &mut rng);
         assert_eq!(utils::nist_lag1d_autocorr(&samples), -1/2);
         plotutils::plot1d(samples, "", "White noise", "tests/white-noise.png");
     }
     #[test]
     fn test_pink_noise_generator() {
-        let samples = pink_noise(-10.0, 1.0, 1000);
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples = pink_noise_with_rng(-10.0, 1.0, 1000, &mut rng);
         assert_eq!(utils::nist_lag1d_autocorr(&samples), 0);
         plotutils::plot1d(samples, "", "Pink noise", "tests/pink-noise.png");
     }
+    #[test]
+    fn test_fft() {
+        let data: Vec<(f64, f64)> = (0..16)
+            .map(|i| ((i as f64).sin(), (i as f64 * 0.3).cos()))
+            .collect();
+        let mut spectrum = data.clone();
+        fft(&mut spectrum, false);
+        // against direct evaluation
+        for (k, x) in spectrum.iter().enumerate() {
+            let (mut re, mut im) = (0.0_f64, 0.0_f64);
+            for (i, d) in data.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (i * k) as f64 / 16.0;
+                re += d.0 * angle.cos() - d.1 * angle.sin();
+                im += d.0 * angle.sin() + d.1 * angle.cos()
+            }
+            assert!((x.0 - re).abs() < 1.0E-9 && (x.1 - im).abs() < 1.0E-9)
+        }
+        fft(&mut spectrum, true);
+        for (x, d) in spectrum.iter().zip(data.iter()) {
+            assert!((x.0 - d.0).abs() < 1.0E-12 && (x.1 - d.1).abs() < 1.0E-12)
+        }
+    }
+    #[test]
+    fn test_powerlaw_noise_generator() {
+        let taus = [1.0_f64, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
+        // least squares log-log slope
+        let slope = |devs: &[f64]| -> f64 {
+            let x: Vec<f64> = taus.iter().map(|t| t.ln()).collect();
+            let y: Vec<f64> = devs.iter().map(|d| d.ln()).collect();
+            let (mx, my) = (statistical::mean(&x), statistical::mean(&y));
+            let num: f64 = x.iter().zip(y.iter()).map(|(x, y)| (x - mx) * (y - my)).sum();
+            let den: f64 = x.iter().map(|x| (x - mx).powf(2.0)).sum();
+            num / den
+        };
+        // (alpha, adev slope, mdev slope)
+        let expected = [
+            (2.0, -1.0, -1.5),
+            (1.0, -1.0, -1.0),
+            (0.0, -0.5, -0.5),
+            (-1.0, 0.0, 0.0),
+            (-2.0, 0.5, 0.5),
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        for (alpha, adev_slope, mdev_slope) in expected {
+            let x = powerlaw_noise_with_rng(alpha, 1.0E-20, 1.0, 16384, &mut rng);
+            assert_eq!(x.len(), 16384);
+            let (adev, _) = crate::deviation(&x, &taus, crate::Deviation::Allan, 1.0, false, true).unwrap();
+            let (mdev, _) = crate::deviation(&x, &taus, crate::Deviation::Modified, 1.0, false, true).unwrap();
+            assert!((slope(&adev) - adev_slope).abs() < 0.15);
+            assert!((slope(&mdev) - mdev_slope).abs() < 0.15);
+            // white FM level: avar = h0 / 2 / tau
+            if alpha == 0.0 {
+                assert!((adev[0] / (0.5E-20_f64).powf(0.5) - 1.0).abs() < 0.05)
+            }
+        }
+        // sampling period: white FM adev @ 1s, 10 Hz sampling
+        let x = powerlaw_noise_with_rng(0.0, 1.0E-20, 0.1, 16384, &mut rng);
+        let (adev, _) = crate::deviation(&x, &[1.0], crate::Deviation::Allan, 10.0, false, true).unwrap();
+        assert!((adev[0] / (0.5E-20_f64).powf(0.5) - 1.0).abs() < 0.1)
+    }
+    #[test]
+    fn test_clock_model() {
+        // deterministic terms only
+        let clock = ClockModel {
+            time_offset: 1.0E-6,
+            frequency_offset: 1.0E-9,
+            drift: 1.0E-12,
+            ..ClockModel::default()
+        };
+        let x = clock.phase(10.0, 100);
+        assert_eq!(x.len(), 100);
+        assert_eq!(x[0], 1.0E-6);
+        let t = 990.0_f64;
+        assert!((x[99] - (1.0E-6 + 1.0E-9 * t + 1.0E-12 * t * t / 2.0)).abs() < 1.0E-18);
+        let y = clock.fractional_frequency(10.0, 100);
+        assert_eq!(y.len(), 100);
+        for (i, y) in y.iter().enumerate() {
+            // mean frequency over [t, t + tau0]
+            let t = (i as f64 + 0.5) * 10.0;
+            assert!((y - (1.0E-9 + 1.0E-12 * t)).abs() < 1.0E-18)
+        }
+        // linear drift: adev = D tau / sqrt(2)
+        let taus = [1.0_f64, 2.0, 4.0, 8.0];
+        let clock = ClockModel {
+            drift: 1.0E-12,
+            ..ClockModel::default()
+        };
+        let (adev, _) = crate::deviation(&clock.phase(1.0, 100), &taus, crate::Deviation::Allan, 1.0, false, true)
+            .unwrap();
+        for (tau, adev) in taus.iter().zip(adev.iter()) {
+            assert!((adev / (1.0E-12 * tau / 2.0_f64.sqrt()) - 1.0).abs() < 1.0E-6)
+        }
+        // white FM + random walk FM
+        let mut rng = StdRng::seed_from_u64(0);
+        let clock = ClockModel {
+            h: [1.0E-26, 0.0, 1.0E-20, 0.0, 0.0],
+            frequency_offset: 1.0E-9,
+            ..ClockModel::default()
+        };
+        let y = clock.fractional_frequency_with_rng(1.0, 16384, &mut rng);
+        let (adev, _) = crate::deviation(&y, &[1.0, 4096.0], crate::Deviation::Allan, 1.0, true, true)
+            .unwrap();
+        // white FM dominates at 1s: avar = h0 / 2 / tau
+        assert!((adev[0] / (0.5E-20_f64).sqrt() - 1.0).abs() < 0.05);
+        // random walk FM raises long term
+        assert!(adev[1] > 2.0 * (0.5E-20_f64 / 4096.0).sqrt());
+    }
+    #[test]
+    fn test_phase_noise_profile() {
+        let profile = [(1.0_f64, -80.0_f64), (100.0, -120.0), (1.0E4, -150.0)];
+        assert_eq!(profile_level(&profile, 0.1), -80.0);
+        assert_eq!(profile_level(&profile, 1.0E5), -150.0);
+        assert!((profile_level(&profile, 10.0) + 100.0).abs() < 1.0E-9);
+        assert!((profile_level(&profile, 1000.0) + 135.0).abs() < 1.0E-9);
+        assert!(phase_noise_profile(&[], 10.0E6, 1.0, 16).is_err());
+        assert!(phase_noise_profile(&[(10.0, -100.0), (1.0, -90.0)], 10.0E6, 1.0, 16).is_err());
+        let mut rng = StdRng::seed_from_u64(0);
+        // flat profile: white PM, var(x) = S_x * fs / 2
+        let nu0 = 10.0E6_f64;
+        let x = phase_noise_profile_with_rng(&[(1.0, -100.0)], nu0, 1.0, 16384, &mut rng)
+            .unwrap();
+        assert_eq!(x.len(), 16384);
+        let s_x = 2.0 * 1.0E-10 / (2.0 * std::f64::consts::PI * nu0).powf(2.0);
+        let var = statistical::variance(&x, Some(0.0));
+        assert!((var / (s_x / 2.0) - 1.0).abs() < 0.05);
+        // -20 dB/decade: white FM, adev ~ tau^-1/2
+        let profile = [(1.0E-5, -20.0), (1.0, -120.0)];
+        let x = phase_noise_profile_with_rng(&profile, nu0, 1.0, 16384, &mut rng)
+            .unwrap();
+        let (adev, _) = crate::deviation(&x, &[2.0, 32.0, 512.0], crate::Deviation::Allan, 1.0, false, true)
+            .unwrap();
+        assert!((adev[1] / adev[0] - 0.25).abs() < 0.05);
+        assert!((adev[2] / adev[1] - 0.25).abs() < 0.05);
+    }
+    #[test]
+    fn test_correlated_noise() {
+        let correlation = vec![
+            vec![1.0, 0.5, 0.0],
+            vec![0.5, 1.0, -0.3],
+            vec![0.0, -0.3, 1.0],
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        let channels = correlated_noise_with_rng(2.0, &[1.0E-20, 4.0E-20, 1.0E-20], 1.0, 16384, &correlation, &mut rng)
+            .unwrap();
+        assert_eq!(channels.len(), 3);
+        let pearson = |a: &[f64], b: &[f64]| -> f64 {
+            let (ma, mb) = (statistical::mean(a), statistical::mean(b));
+            let mut num = 0.0_f64;
+            let (mut da, mut db) = (0.0_f64, 0.0_f64);
+            for (a, b) in a.iter().zip(b.iter()) {
+                num += (a - ma) * (b - mb);
+                da += (a - ma).powf(2.0);
+                db += (b - mb).powf(2.0)
+            }
+            num / (da * db).sqrt()
+        };
+        for i in 0..3 {
+            assert_eq!(channels[i].len(), 16384);
+            for j in 0..i {
+                assert!((pearson(&channels[i], &channels[j]) - correlation[i][j]).abs() < 0.03)
+            }
+        }
+        // levels follow h_alpha
+        let var = |x: &[f64]| statistical::variance(x, None);
+        assert!((var(&channels[1]) / var(&channels[0]) - 4.0).abs() < 0.2);
+        // invalid matrices
+        let not_symmetric = vec![vec![1.0, 0.5], vec![0.0, 1.0]];
+        assert!(correlated_noise(2.0, &[1.0, 1.0], 1.0, 16, &not_symmetric).is_err());
+        let not_definite = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        assert!(correlated_noise(2.0, &[1.0, 1.0], 1.0, 16, &not_definite).is_err());
+        assert!(correlated_noise(2.0, &[1.0], 1.0, 16, &not_definite).is_err());
+    }
 }
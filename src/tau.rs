@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::Deviation;
 
 /// Lists all `TauAxis` known to the generator
 #[derive(Clone, Copy)]
@@ -17,6 +18,8 @@ pub enum Error {
     NullTauValue, 
     #[error("`tau` axis should only comprise increasing values")]
     InvalidTauShape,
+    #[error("`tau` should be 0.75 * even multiple of sampling period, for theo statistics")]
+    InvalidTheoTau,
 }
 
 impl Default for TauAxis {
@@ -46,6 +49,40 @@ pub fn tau_sanity_checks (taus: &Vec<f64>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Returns true if `tau_u` is an integer multiple of sampling period
+fn is_integer_multiple (tau_u: f64) -> bool {
+    tau_u >= 1.0_f64 && (tau_u - tau_u.round()).abs() < 1.0E-6
+}
+
+/// Returns true if `tau_u` is 0.75 * even multiple of sampling period
+pub(crate) fn is_theo_multiple (tau_u: f64) -> bool {
+    let m = tau_u / 0.75_f64;
+    is_integer_multiple(m) && (m.round() as u64).is_multiple_of(2)
+}
+
+/// Returns Ok() if given tau axis passes standard sanity checks
+/// and is feasible for desired deviation.   
+/// Theo1 & TheoBR are evaluated at tau = 0.75 * m * tau0, m even.   
+/// TheoH also accepts integer multiples of tau0,
+/// evaluated as Allan deviation.   
+/// sample_rate: sampling rate (Hz)
+pub fn tau_feasibility_checks (taus: &[f64], calc: Deviation, sample_rate: f64) -> Result<(), Error> {
+    tau_sanity_checks(&taus.to_vec())?;
+    for tau in taus {
+        let tau_u = tau * sample_rate;
+        match calc {
+            Deviation::Theo1 | Deviation::TheoBR if !is_theo_multiple(tau_u) => {
+                return Err(Error::InvalidTheoTau)
+            },
+            Deviation::TheoH if !is_theo_multiple(tau_u) && !is_integer_multiple(tau_u) => {
+                return Err(Error::InvalidTheoTau)
+            },
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
 /// Generate log(base) `TauAxis`
 /// ranging from [tau_0: tau_m]
 fn log_n_tau_generator (tau_0: f64, tau_m: f64, base: f64) -> Vec<f64> {
@@ -99,4 +136,25 @@ pub fn tau_generator (axis: TauAxis, tau_0: f64, tau_m: f64) -> Vec<f64> {
             assert_eq!(taus[i], i as f64 +1.0)
         }
     }
+
+    #[test]
+    /// Tests `Tau` feasibility rules
+    fn test_tau_feasibility() {
+        let taus = tau_generator(TauAxis::Octave, 1.0_f64, 1024.0_f64);
+        assert!(tau_feasibility_checks(&taus, Deviation::Allan, 1.0_f64).is_ok());
+        assert!(tau_feasibility_checks(&taus, Deviation::Theo1, 1.0_f64).is_err());
+        assert!(tau_feasibility_checks(&taus, Deviation::TheoH, 1.0_f64).is_ok());
+        // 0.75 * [2, 4, 8, ...]
+        let taus = tau_generator(TauAxis::Octave, 1.5_f64, 1024.0_f64);
+        assert!(tau_feasibility_checks(&taus, Deviation::Theo1, 1.0_f64).is_ok());
+        assert!(tau_feasibility_checks(&taus, Deviation::TheoBR, 1.0_f64).is_ok());
+        assert!(tau_feasibility_checks(&taus, Deviation::TheoH, 1.0_f64).is_ok());
+        // 0.75 * [2, 4, 8, ...] @ 2 Hz
+        let taus = tau_generator(TauAxis::Octave, 0.75_f64, 1024.0_f64);
+        assert!(tau_feasibility_checks(&taus, Deviation::Theo1, 2.0_f64).is_ok());
+        // m = 1 is odd
+        let taus = vec![0.75_f64, 1.5_f64];
+        assert!(tau_feasibility_checks(&taus, Deviation::Theo1, 1.0_f64).is_err());
+        assert!(tau_feasibility_checks(&taus, Deviation::TheoH, 1.0_f64).is_err());
+    }
 }
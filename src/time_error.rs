@@ -0,0 +1,113 @@
+//! Time error statistics, used to qualify
+//! time transfer links & network synchronization
+//! (ITU-T G.810 / G.8260 definitions)
+
+use crate::{tau, Error};
+
+/// Time error statistics, over a whole record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeError {
+    /// max |TE| (s)
+    pub max_abs: f64,
+    /// constant time error: mean TE (s)
+    pub cte: f64,
+    /// peak to peak TE (s)
+    pub pk_pk: f64,
+}
+
+/// Computes time error statistics over given record.   
+/// data: phase / time error data (s)   
+/// returns: max|TE|, cTE and peak to peak TE
+pub fn time_error (data: &[f64]) -> Result<TimeError, Error> {
+    if data.is_empty() {
+        return Err(Error::NotEnoughSamplesError)
+    }
+    let cte = statistical::mean(data);
+    let mut max_abs = 0.0_f64;
+    let (mut min, mut max) = (f64::MAX, f64::MIN);
+    for x in data {
+        max_abs = max_abs.max(x.abs());
+        min = min.min(*x);
+        max = max.max(*x)
+    }
+    Ok(TimeError {
+        max_abs,
+        cte,
+        pk_pk: max - min,
+    })
+}
+
+/// Returns dynamic time error serie, TE - cTE.   
+/// This serie can then be passed to `mtie()`, or to `deviation()`   
+/// for `Deviation::Time` analysis.   
+/// This serie is not filtered: G.8271.1 dTE(L) additionally applies
+/// a 0.1 Hz low pass filter, which is left to the caller.   
+/// data: phase / time error data (s)
+pub fn dynamic_time_error (data: &[f64]) -> Vec<f64> {
+    if data.is_empty() {
+        return Vec::new()
+    }
+    let cte = statistical::mean(data);
+    data.iter()
+        .map(|x| x - cte)
+        .collect()
+}
+
+/// Computes Time Interval Error rms (TIE rms)   
+/// for desired observation intervals.   
+/// data: phase / time error data (s)   
+/// taus: desired observation intervals (s)   
+/// sample_rate: sampling rate (Hz)   
+/// returns: tie rms for each feasible observation interval
+pub fn tie_rms (data: &[f64], taus: &[f64], sample_rate: f64) -> Result<Vec<f64>, Error> {
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    let mut ret: Vec<f64> = Vec::with_capacity(taus.len());
+    for tau in taus {
+        let tau_u = (tau * sample_rate) as usize;
+        if tau_u == 0 || tau_u >= data.len() {
+            break
+        }
+        let n = data.len() - tau_u;
+        let mut sum = 0.0_f64;
+        for i in 0..n {
+            sum += (data[i+tau_u] - data[i]).powf(2.0_f64)
+        }
+        ret.push((sum / n as f64).powf(0.5_f64))
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_time_error() {
+        let data: Vec<f64> = vec![1.0E-9, -3.0E-9, 2.0E-9, 4.0E-9];
+        let te = time_error(&data).unwrap();
+        assert_eq!(te.max_abs, 4.0E-9);
+        assert!((te.cte - 1.0E-9).abs() < 1.0E-18);
+        assert!((te.pk_pk - 7.0E-9).abs() < 1.0E-18);
+        let dte = dynamic_time_error(&data);
+        assert!((statistical::mean(&dte)).abs() < 1.0E-18);
+        assert!(time_error(&[]).is_err());
+    }
+    #[test]
+    fn test_tie_rms() {
+        // constant frequency offset
+        let data: Vec<f64> = (0..100)
+            .map(|i| 1.0E-9 * i as f64)
+            .collect();
+        let taus = tau::tau_generator(tau::TauAxis::Octave, 1.0_f64, 1000.0_f64);
+        let ties = tie_rms(&data, &taus, 1.0_f64).unwrap();
+        assert_eq!(ties.len(), 7);
+        for i in 0..ties.len() {
+            assert!((ties[i] - 1.0E-9 * taus[i]).abs() < 1.0E-15)
+        }
+        // 2 Hz sampling
+        let ties = tie_rms(&data, &taus, 2.0_f64).unwrap();
+        assert_eq!(ties.len(), 6);
+        for i in 0..ties.len() {
+            assert!((ties[i] - 2.0E-9 * taus[i]).abs() < 1.0E-15)
+        }
+    }
+}
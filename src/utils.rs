@@ -3,6 +3,7 @@
 
 use rand::prelude::*;
 use rand_distr::StandardNormal;
+use crate::{tau, Error};
 
 /// numpy::cumsum direct equivalent 
 pub fn cumsum (data: &Vec<f64>, normalization: Option<f64>) -> Vec<f64> {
@@ -25,9 +26,15 @@ pub fn diff (data: &Vec<f64>, normalization: Option<f64>) -> Vec<f64> {
 
 /// Generate `size` random symbols  0 < x <= 1.0f
 pub fn random (size: usize) -> Vec<f64> {
+    random_with_rng(size, &mut rand::thread_rng())
+}
+
+/// Generate `size` random symbols, drawn from given random generator.   
+/// Use a seeded generator for reproducible series
+pub fn random_with_rng<R: Rng> (size: usize, rng: &mut R) -> Vec<f64> {
     let mut ret: Vec<f64> = Vec::with_capacity(size);
     for _ in 0..size {
-        ret.push(rand::thread_rng().sample(StandardNormal))
+        ret.push(rng.sample(StandardNormal))
     }
     ret
 }
@@ -102,6 +109,231 @@ pub fn nist_lag1d_autocorr (data: &Vec<f64>) -> i32 {
     (-alpha -1)/2
 }
 
+/// Forms the pairwise difference series A-B, B-C and C-A,
+/// as expected by `three_cornered_hat`, from three clock series.   
+/// returns: (a - b, b - c, c - a)
+#[allow(clippy::type_complexity)]
+pub fn three_cornered_differences (a: &[f64], b: &[f64], c: &[f64]) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>), Error> {
+    if a.len() != b.len() || b.len() != c.len() {
+        return Err(Error::SeriesLengthMismatch)
+    }
+    let diff = |x: &[f64], y: &[f64]| -> Vec<f64> {
+        x.iter()
+            .zip(y.iter())
+            .map(|(x, y)| x - y)
+            .collect()
+    };
+    Ok((diff(a, b), diff(b, c), diff(c, a)))
+}
+
+/// Converts input serie to phase data,
+/// fractional data are integrated with a unit step
+fn to_phase (data: &[f64], is_fractional: bool) -> Vec<f64> {
+    match is_fractional {
+        true => fractional_integral(&data.to_vec(), 1.0_f64),
+        false => data.to_vec(),
+    }
+}
+
+/// Identifies dominant power law `alpha` (S_y(f) ~ f^alpha)   
+/// at averaging factor `tau_u`, using lag1 autocorrelation   
+/// (NIST SP1065 §5.5.6): phase data are decimated, fractional data   
+/// are averaged, then differenced until lag1 autocorrelation drops.   
+/// dmax: maximal differencing order (2 for adev, 3 for hdev)   
+/// returns unrounded alpha, None if less than 30 samples remain
+pub(crate) fn lag1_alpha (data: &[f64], tau_u: usize, dmax: usize, is_fractional: bool) -> Option<f64> {
+    let tau_u = tau_u.max(1);
+    let mut z: Vec<f64> = match is_fractional {
+        true => data.chunks_exact(tau_u)
+            .map(|c| c.iter().sum::<f64>() / tau_u as f64)
+            .collect(),
+        false => data.iter()
+            .step_by(tau_u)
+            .copied()
+            .collect(),
+    };
+    let mut d: usize = 0;
+    loop {
+        if z.len() < 30 {
+            return None
+        }
+        let mean = statistical::mean(&z);
+        let mut num = 0.0_f64;
+        let mut den = 0.0_f64;
+        for i in 0..z.len()-1 {
+            num += (z[i] - mean) * (z[i+1] - mean);
+            den += (z[i] - mean).powf(2.0_f64)
+        }
+        den += (z[z.len()-1] - mean).powf(2.0_f64);
+        let r1 = num / den;
+        let delta = r1 / (1.0_f64 + r1);
+        if delta < 0.25_f64 || d >= dmax {
+            let p = -2.0_f64 * (delta + d as f64);
+            return match is_fractional {
+                true => Some(p),
+                false => Some(p + 2.0_f64),
+            }
+        }
+        z = diff(&z, None);
+        d += 1
+    }
+}
+
+/// Identifies power law contained in given serie, for each tau,   
+/// with the NIST lag1 autocorrelation method.   
+/// Unlike `nist_lag1d_autocorr`, noise processes are identified   
+/// at every averaging time.   
+/// data: input data serie   
+/// taus: desired `tau` offsets (s)   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input serie is made of fractional (n.a) data   
+/// dmax: maximal differencing order, 2 for Allan statistics,   
+/// 3 for Hadamard statistics   
+/// returns: (alpha, alpha_raw) : rounded and unrounded fractional frequency   
+/// PSD slope, for each `tau` where at least 30 samples remain.   
+/// Rounded alpha does not exceed 2 (white PM)
+pub fn lag1_noise_id (data: &[f64], taus: &[f64], sample_rate: f64, is_fractional: bool, dmax: usize)
+        -> Result<(Vec<i32>, Vec<f64>), Error>
+{
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    let mut alphas: Vec<i32> = Vec::with_capacity(taus.len());
+    let mut raws: Vec<f64> = Vec::with_capacity(taus.len());
+    for tau in taus {
+        let tau_u = (tau * sample_rate) as usize;
+        if tau_u == 0 {
+            break
+        }
+        match lag1_alpha(data, tau_u, dmax, is_fractional) {
+            Some(alpha) => {
+                alphas.push(alpha.round().min(2.0_f64) as i32);
+                raws.push(alpha)
+            },
+            None => break,
+        }
+    }
+    Ok((alphas, raws))
+}
+
+/// Computes Barnes B1 bias ratio: standard (N-sample) variance
+/// over Allan variance of the N frequency averages at given tau.   
+/// data: input data serie   
+/// tau_u: averaging factor (samples)   
+/// is_fractional: true if input serie is made of fractional (n.a) data   
+/// returns: B1 ratio, None if less than 2 averages fit in the serie
+pub fn b1_ratio (data: &[f64], tau_u: usize, is_fractional: bool) -> Option<f64> {
+    let x = to_phase(data, is_fractional);
+    if tau_u == 0 || x.is_empty() {
+        return None
+    }
+    let y: Vec<f64> = x.iter()
+        .step_by(tau_u)
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|w| (w[1] - w[0]) / tau_u as f64)
+        .collect();
+    if y.len() < 2 {
+        return None
+    }
+    let n = y.len() as f64;
+    let mean = statistical::mean(&y);
+    let var = y.iter()
+        .map(|y| (y - mean).powf(2.0_f64))
+        .sum::<f64>() / (n - 1.0_f64);
+    let avar = y.windows(2)
+        .map(|w| (w[1] - w[0]).powf(2.0_f64))
+        .sum::<f64>() / 2.0_f64 / (n - 1.0_f64);
+    Some(var / avar)
+}
+
+/// Returns expected B1 ratio for N averages
+/// and a tau^mu Allan variance power law
+pub fn b1_expected (n: f64, mu: f64) -> f64 {
+    if mu.abs() < 1.0E-9 {
+        n * n.ln() / 2.0_f64 / (n - 1.0_f64) / 2.0_f64.ln()
+    } else {
+        n * (1.0_f64 - n.powf(mu)) / 2.0_f64 / (n - 1.0_f64) / (1.0_f64 - 2.0_f64.powf(mu))
+    }
+}
+
+/// Computes R(n) ratio: modified Allan variance
+/// over (overlapping) Allan variance at given tau.   
+/// data: input data serie   
+/// tau_u: averaging factor (samples)   
+/// is_fractional: true if input serie is made of fractional (n.a) data   
+/// returns: R(n) ratio, None if modified Allan variance is not feasible
+pub fn rn_ratio (data: &[f64], tau_u: usize, is_fractional: bool) -> Option<f64> {
+    let x = to_phase(data, is_fractional);
+    if tau_u == 0 || 3 * tau_u >= x.len() {
+        return None
+    }
+    let (adev, _) = crate::calc_adev(&x, tau_u as f64, 1.0_f64, true).ok()?;
+    let (mdev, _) = crate::calc_mdev(&x, tau_u as f64, 1.0_f64).ok()?;
+    Some((mdev / adev).powf(2.0_f64))
+}
+
+/// Returns expected R(n) ratio for white (alpha = 2)
+/// and flicker (alpha = 1) phase noise
+fn rn_expected (n: f64, alpha: i32) -> f64 {
+    match alpha {
+        2 => 1.0_f64 / n,
+        _ => 3.37_f64 / (1.038_f64 + 3.0_f64 * (std::f64::consts::PI * n).ln()),
+    }
+}
+
+/// Identifies power law contained in given serie, for each tau,
+/// with the B1 + R(n) method: B1 ratio discriminates
+/// frequency noises, R(n) ratio then discriminates phase noises.   
+/// Unlike `nist_lag1d_autocorr` this method does not expect
+/// a single noise process to dominate the whole serie.   
+/// data: input data serie   
+/// taus: desired `tau` offsets (s)   
+/// sample_rate: sampling rate (Hz)   
+/// is_fractional: true if input serie is made of fractional (n.a) data   
+/// returns: alpha, the fractional frequency PSD slope, for each feasible `tau`
+pub fn b1_noise_id (data: &[f64], taus: &[f64], sample_rate: f64, is_fractional: bool) -> Result<Vec<i32>, Error> {
+    tau::tau_sanity_checks(&taus.to_vec())?;
+    let mut alphas: Vec<i32> = Vec::with_capacity(taus.len());
+    for tau in taus {
+        let tau_u = (tau * sample_rate) as usize;
+        let (b1, rn) = match (b1_ratio(data, tau_u, is_fractional), rn_ratio(data, tau_u, is_fractional)) {
+            (Some(b1), Some(rn)) => (b1, rn),
+            _ => break,
+        };
+        let n = (to_phase(data, is_fractional).len() - 1) / tau_u;
+        let n = n as f64;
+        // mu = 1: RW FM, mu = 0: flicker FM, mu = -1: white FM, mu = -2: PM noises,
+        // boundaries lie at the geometric mean of neighbouring expectations
+        let mut mu = -2;
+        for candidate in [1, 0, -1] {
+            let boundary = (b1_expected(n, candidate as f64) * b1_expected(n, candidate as f64 - 1.0_f64))
+                .powf(0.5_f64);
+            if b1 > boundary {
+                mu = candidate;
+                break
+            }
+        }
+        let alpha = match mu {
+            // R(1) = 1 whatever the noise: rely on lag1 autocorrelation
+            -2 if tau_u == 1 => {
+                lag1_alpha(data, 1, 2, is_fractional)
+                    .map(|alpha| (alpha.round() as i32).clamp(1, 2))
+                    .unwrap_or(2)
+            },
+            -2 => {
+                let m = tau_u as f64;
+                let boundary = (rn_expected(m, 2) * rn_expected(m, 1)).powf(0.5_f64);
+                match rn < boundary {
+                    true => 2,
+                    false => 1,
+                }
+            },
+            mu => -mu - 1,
+        };
+        alphas.push(alpha)
+    }
+    Ok(alphas)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,15 +504,75 @@ mod tests {
     
     #[test]
     fn test_powerlaw_whitepm() {
-        let samples = diff(&white_noise(-10.0, 1.0, 1000), None);
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples = diff(&white_noise_with_rng(-10.0, 1.0, 1000, &mut rng), None);
         assert_eq!(nist_lag1d_autocorr(&samples),-3/2);
         plot1d(samples, "", "PM white", "tests/white-phasenoise.png");
     }
     
     #[test]
     fn test_powerlaw_whitefm() {
-        let samples = diff(&pink_noise(-10.0, 1.0, 1000), None);
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples = diff(&pink_noise_with_rng(-10.0, 1.0, 1000, &mut rng), None);
         assert_eq!(nist_lag1d_autocorr(&samples), -1);
         plot1d(samples, "", "PM flicker", "tests/flicker-phasenoise.png");
     }
+    
+    #[test]
+    fn test_b1_noise_id() {
+        // expected ratios
+        for n in [4.0_f64, 16.0, 256.0] {
+            assert!((b1_expected(n, -1.0) - 1.0).abs() < 1.0E-12);
+            assert!((b1_expected(n, 1.0) - n / 2.0).abs() < 1.0E-12);
+            assert!((b1_expected(n, 1.0E-6) / b1_expected(n, 0.0) - 1.0).abs() < 1.0E-4)
+        }
+        let mut rng = StdRng::seed_from_u64(0);
+        let white: Vec<f64> = (0..8192)
+            .map(|_| rng.sample(StandardNormal))
+            .collect();
+        assert!((rn_ratio(&white, 1, false).unwrap() - 1.0).abs() < 1.0E-12);
+        let taus = [1.0_f64, 2.0, 4.0, 8.0, 16.0, 32.0];
+        // white PM
+        let alphas = b1_noise_id(&white, &taus, 1.0_f64, false).unwrap();
+        assert_eq!(alphas, vec![2; 6]);
+        // white FM
+        let alphas = b1_noise_id(&white, &taus, 1.0_f64, true).unwrap();
+        assert_eq!(alphas, vec![0; 6]);
+        // random walk FM
+        let rw = cumsum(&white, None);
+        let alphas = b1_noise_id(&rw, &taus, 1.0_f64, true).unwrap();
+        assert_eq!(alphas, vec![-2; 6]);
+        // unfeasible taus are dropped
+        let alphas = b1_noise_id(&white[..64], &taus, 1.0_f64, true).unwrap();
+        assert_eq!(alphas.len(), 5);
+    }
+    
+    #[test]
+    fn test_lag1_noise_id() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let white: Vec<f64> = (0..8192)
+            .map(|_| rng.sample(StandardNormal))
+            .collect();
+        let taus = [1.0_f64, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0];
+        // white PM
+        let (alphas, raws) = lag1_noise_id(&white, &taus, 1.0_f64, false, 2).unwrap();
+        assert_eq!(alphas, vec![2; 9]); // 8192/512 < 30
+        for (alpha, raw) in alphas.iter().zip(raws.iter()) {
+            assert!(*alpha as f64 - raw <= 0.5)
+        }
+        // white FM, either as phase or fractional data,
+        // at least 256 samples remain
+        let phase = cumsum(&white, None);
+        let (alphas, _) = lag1_noise_id(&phase, &taus, 1.0_f64, false, 2).unwrap();
+        assert_eq!(alphas.len(), 9);
+        assert_eq!(alphas[..6], [0; 6]);
+        let (alphas, _) = lag1_noise_id(&white, &taus, 1.0_f64, true, 2).unwrap();
+        assert_eq!(alphas[..6], [0; 6]);
+        // random walk FM
+        let (alphas, _) = lag1_noise_id(&phase, &taus, 1.0_f64, true, 2).unwrap();
+        assert_eq!(alphas[..6], [-2; 6]);
+        // sampling rate
+        let (alphas, _) = lag1_noise_id(&white, &taus, 2.0_f64, false, 2).unwrap();
+        assert_eq!(alphas.len(), 8);
+    }
 }